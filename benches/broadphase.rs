@@ -0,0 +1,105 @@
+//! Headless collision benchmark.
+//!
+//! Steps a crowded co-op run through [`World::step`] for a few seconds of game
+//! time without opening a window, so collision, hit resolution and everything
+//! around them run with the game's own colliders. Both ships sweep the arena
+//! firing every step, with slow bullets that pile up into thousands before
+//! the measurement starts and pierce the enemies without killing them. Checks
+//! a step stays inside a 60 FPS frame budget.
+//!
+//!     cargo bench --bench broadphase
+
+use macroquad::prelude::*;
+use my_game::difficulty::Preset;
+use my_game::pool::PoolCaps;
+use my_game::world::{Coop, Input, SimParams, World};
+use std::time::{Duration, Instant};
+
+const ARENA: Vec2 = vec2(1280.0, 720.0);
+/// Live bullets the run builds up to before it is measured.
+const BULLETS: usize = 3000;
+const FRAMES: usize = 600;
+const DELTA: f32 = 1.0 / 60.0;
+const FRAME_BUDGET: Duration = Duration::from_micros(16_667);
+
+fn crowded_world() -> World {
+    let params = SimParams {
+        // Ships that never go down, firing every step.
+        player_hp: u32::MAX,
+        player_speed: 1200.0,
+        fire_cooldown: 0.0,
+        // Slow enough to cross the arena in about half a minute, and
+        // harmless, so that every bullet keeps hitting enemies that keep
+        // coming down instead of clearing the arena.
+        bullet_speed: 25.0,
+        bullet_damage: 0,
+        bullet_pierce: u32::MAX,
+        spawn_rate_scale: 100.0,
+        coop: Some(Coop {
+            shared_lives: false,
+            friendly_fire: false,
+        }),
+        ..SimParams::default()
+    };
+    let caps = PoolCaps {
+        projectiles: 5000,
+        enemies: 400,
+        ..PoolCaps::default()
+    };
+    World::new(ARENA, Preset::Insane.difficulty(), caps, 42, params)
+}
+
+/// Both ships sweep side to side, the second against the first, at the
+/// bottom of the arena.
+fn inputs(frame: usize) -> [Input; 2] {
+    let sweep = if (frame / 60).is_multiple_of(2) {
+        1.0
+    } else {
+        -1.0
+    };
+    [sweep, -sweep].map(|x| Input {
+        movement: vec2(x, 1.0),
+        fire: true,
+    })
+}
+
+fn main() {
+    let mut world = crowded_world();
+    let mut frame = 0;
+    while world.projectiles.len() < BULLETS {
+        world.step(&inputs(frame), DELTA);
+        frame += 1;
+        assert!(!world.game_over, "the run ended while warming up");
+    }
+
+    let mut total = Duration::ZERO;
+    let mut worst = Duration::ZERO;
+    let (mut projectiles, mut enemies) = (0, 0);
+    for _ in 0..FRAMES {
+        let inputs = inputs(frame);
+        frame += 1;
+        let start = Instant::now();
+        world.step(&inputs, DELTA);
+        let elapsed = start.elapsed();
+        total += elapsed;
+        worst = worst.max(elapsed);
+        projectiles += world.projectiles.len();
+        enemies += world.enemies.len();
+    }
+    let mean = total / FRAMES as u32;
+    println!(
+        "World::step with {} projectiles x {} enemies on average: mean {:?}, worst {:?} per frame",
+        projectiles / FRAMES,
+        enemies / FRAMES,
+        mean,
+        worst
+    );
+
+    if mean > FRAME_BUDGET {
+        eprintln!(
+            "World::step exceeds the 60 FPS budget of {:?}",
+            FRAME_BUDGET
+        );
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,294 @@
+//! Achievements.
+//!
+//! Each achievement is a row in [`ACHIEVEMENTS`] with a [`Goal`]; adding one
+//! needs no code unless it needs a new kind of goal. Progress comes from the
+//! gameplay events of live runs. Unlocks and lifetime counters are stored as
+//! plain text, `unlocked id` and `progress id count` lines.
+
+use crate::enemy::EnemyKind;
+use crate::events::{Event, Subscriber};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+
+pub const ACHIEVEMENTS_PATH: &str = "achievements.dat";
+
+#[derive(Clone, Copy, Debug)]
+pub enum Goal {
+    /// Destroy `count` enemies over all runs, of one kind or any.
+    Kills { kind: Option<EnemyKind>, count: u32 },
+    /// Reach `points` in one run, optionally without ever moving down.
+    Score { points: u32, never_down: bool },
+    /// Stay alive for `seconds` in one run.
+    Survive { seconds: f32 },
+    /// Reach a combo chain of `length` kills.
+    Chain { length: u32 },
+    /// Score more than the best score on the leaderboard when the run began.
+    BeatHighScore,
+}
+
+pub struct AchievementDef {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub goal: Goal,
+}
+
+pub static ACHIEVEMENTS: &[AchievementDef] = &[
+    AchievementDef {
+        id: "first_blood",
+        name: "First Blood",
+        description: "Destroy an enemy",
+        goal: Goal::Kills {
+            kind: None,
+            count: 1,
+        },
+    },
+    AchievementDef {
+        id: "big_game",
+        name: "Big Game Hunter",
+        description: "Destroy 100 big enemies",
+        goal: Goal::Kills {
+            kind: Some(EnemyKind::Big),
+            count: 100,
+        },
+    },
+    AchievementDef {
+        id: "exterminator",
+        name: "Exterminator",
+        description: "Destroy 1000 enemies",
+        goal: Goal::Kills {
+            kind: None,
+            count: 1000,
+        },
+    },
+    AchievementDef {
+        id: "never_back_down",
+        name: "Never Back Down",
+        description: "Score 5000 without moving down",
+        goal: Goal::Score {
+            points: 5000,
+            never_down: true,
+        },
+    },
+    AchievementDef {
+        id: "high_roller",
+        name: "High Roller",
+        description: "Score 10000 in one run",
+        goal: Goal::Score {
+            points: 10000,
+            never_down: false,
+        },
+    },
+    AchievementDef {
+        id: "survivor",
+        name: "Survivor",
+        description: "Survive 3 minutes",
+        goal: Goal::Survive { seconds: 180.0 },
+    },
+    AchievementDef {
+        id: "chain_reaction",
+        name: "Chain Reaction",
+        description: "Reach a 24 kill chain",
+        goal: Goal::Chain { length: 24 },
+    },
+    AchievementDef {
+        id: "personal_best",
+        name: "Personal Best",
+        description: "Beat your high score",
+        goal: Goal::BeatHighScore,
+    },
+];
+
+/// Progress within the current run.
+#[derive(Default)]
+struct RunProgress {
+    active: bool,
+    score: u32,
+    time: f32,
+    moved_down: bool,
+    best_chain: u32,
+    high_score: u32,
+}
+
+#[derive(Default)]
+pub struct Achievements {
+    unlocked: BTreeSet<String>,
+    /// Lifetime counters by achievement id.
+    progress: BTreeMap<String, u32>,
+    run: RunProgress,
+    newly_unlocked: Vec<&'static AchievementDef>,
+}
+
+impl Achievements {
+    pub fn load(path: &str) -> Self {
+        let mut achievements = Achievements::default();
+        if let Ok(contents) = fs::read_to_string(path) {
+            for line in contents.lines() {
+                let mut parts = line.split_whitespace();
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some("unlocked"), Some(id), None) => {
+                        achievements.unlocked.insert(id.to_string());
+                    }
+                    (Some("progress"), Some(id), Some(count)) => {
+                        if let Ok(count) = count.parse() {
+                            achievements.progress.insert(id.to_string(), count);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        achievements
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let mut contents = String::new();
+        for id in &self.unlocked {
+            contents.push_str(&format!("unlocked {}\n", id));
+        }
+        for (id, count) in &self.progress {
+            contents.push_str(&format!("progress {} {}\n", id, count));
+        }
+        fs::write(path, contents)
+    }
+
+    pub fn is_unlocked(&self, achievement: &AchievementDef) -> bool {
+        self.unlocked.contains(achievement.id)
+    }
+
+    /// Lifetime progress towards a counted goal, as `(current, target)`.
+    pub fn progress(&self, achievement: &AchievementDef) -> Option<(u32, u32)> {
+        match achievement.goal {
+            Goal::Kills { count, .. } => {
+                let current = self.progress.get(achievement.id).copied().unwrap_or(0);
+                Some((current.min(count), count))
+            }
+            _ => None,
+        }
+    }
+
+    /// Starts tracking a live run. `high_score` is the score to beat.
+    pub fn start_run(&mut self, high_score: u32) {
+        self.run = RunProgress {
+            active: true,
+            high_score,
+            ..RunProgress::default()
+        };
+    }
+
+    pub fn end_run(&mut self) {
+        self.run.active = false;
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        if self.run.active {
+            self.run.time += delta;
+            self.check();
+        }
+    }
+
+    /// Achievements unlocked since the last call.
+    pub fn take_unlocked(&mut self) -> Vec<&'static AchievementDef> {
+        std::mem::take(&mut self.newly_unlocked)
+    }
+
+    fn check(&mut self) {
+        for achievement in ACHIEVEMENTS {
+            if self.is_unlocked(achievement) {
+                continue;
+            }
+            let run = &self.run;
+            let done = match achievement.goal {
+                Goal::Kills { count, .. } => {
+                    self.progress.get(achievement.id).copied().unwrap_or(0) >= count
+                }
+                Goal::Score { points, never_down } => {
+                    run.score >= points && !(never_down && run.moved_down)
+                }
+                Goal::Survive { seconds } => run.time >= seconds,
+                Goal::Chain { length } => run.best_chain >= length,
+                Goal::BeatHighScore => run.high_score > 0 && run.score > run.high_score,
+            };
+            if done {
+                self.unlocked.insert(achievement.id.to_string());
+                self.newly_unlocked.push(achievement);
+            }
+        }
+    }
+}
+
+impl Subscriber for Achievements {
+    fn notify(&mut self, event: &Event) {
+        if !self.run.active {
+            return;
+        }
+        match *event {
+            Event::ShipMoved { movement } if movement.y > 0.0 => self.run.moved_down = true,
+            Event::EnemyKilled {
+                kind,
+                points,
+                chain,
+                ..
+            } => {
+                self.run.score += points;
+                self.run.best_chain = self.run.best_chain.max(chain);
+                for achievement in ACHIEVEMENTS {
+                    if let Goal::Kills { kind: wanted, .. } = achievement.goal {
+                        if wanted.is_none_or(|wanted| wanted == kind) {
+                            *self.progress.entry(achievement.id.to_string()).or_default() += 1;
+                        }
+                    }
+                }
+            }
+            // Points can come from more than kills, as in Pacifist, so the
+            // score goals are settled against the run's final score.
+            Event::RunEnded { score } => self.run.score = score,
+            _ => return,
+        }
+        self.check();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unlocked(achievements: &mut Achievements) -> Vec<&'static str> {
+        achievements
+            .take_unlocked()
+            .iter()
+            .map(|achievement| achievement.id)
+            .collect()
+    }
+
+    #[test]
+    fn score_without_kills_counts_at_the_end_of_the_run() {
+        // Pacifist scores by surviving, not by killing.
+        let mut achievements = Achievements::default();
+        achievements.start_run(100);
+        achievements.notify(&Event::RunEnded { score: 12000 });
+        let ids = unlocked(&mut achievements);
+        assert!(ids.contains(&"high_roller"), "{:?}", ids);
+        assert!(ids.contains(&"never_back_down"), "{:?}", ids);
+        assert!(ids.contains(&"personal_best"), "{:?}", ids);
+    }
+
+    #[test]
+    fn the_final_score_must_still_reach_the_goal() {
+        let mut achievements = Achievements::default();
+        achievements.start_run(20000);
+        achievements.notify(&Event::RunEnded { score: 4999 });
+        assert!(unlocked(&mut achievements).is_empty());
+    }
+
+    #[test]
+    fn moving_down_rules_out_never_back_down() {
+        let mut achievements = Achievements::default();
+        achievements.start_run(0);
+        achievements.notify(&Event::ShipMoved {
+            movement: macroquad::math::vec2(0.0, 1.0),
+        });
+        achievements.notify(&Event::RunEnded { score: 12000 });
+        assert_eq!(unlocked(&mut achievements), ["high_roller"]);
+    }
+}
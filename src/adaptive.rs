@@ -0,0 +1,239 @@
+//! Adaptive difficulty.
+//!
+//! Every few seconds the player's recent accuracy, kill rate, near misses and
+//! deaths are folded into a skill estimate between -1.0 and 1.0. The current
+//! level moves towards that estimate by at most [`MAX_STEP`] per evaluation
+//! and scales spawn density and enemy speed within fixed bounds, so the game
+//! never swings far from the chosen preset.
+
+use crate::stats::RunStats;
+
+const EVALUATION_INTERVAL: f32 = 5.0;
+const MAX_STEP: f32 = 0.1;
+const MAX_SPAWN_SWING: f32 = 0.3;
+const MAX_SPEED_SWING: f32 = 0.2;
+/// Seconds a death keeps counting as "recent".
+const DEATH_MEMORY: f64 = 300.0;
+const TARGET_ACCURACY: f32 = 0.5;
+const TARGET_KILL_RATE: f32 = 1.5;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Adjustment {
+    /// Seconds into the run.
+    pub time: f32,
+    pub level: f32,
+    pub spawn_scale: f32,
+    pub speed_scale: f32,
+}
+
+#[derive(Default)]
+struct Snapshot {
+    shots_fired: u32,
+    shots_hit: u32,
+    kills: u32,
+    near_misses: u32,
+}
+
+#[derive(Default)]
+pub struct Adaptive {
+    level: f32,
+    timer: f32,
+    last: Snapshot,
+    death_times: Vec<f64>,
+}
+
+impl Adaptive {
+    pub fn new() -> Self {
+        Adaptive::default()
+    }
+
+    /// Resets the per-run bookkeeping. The level itself carries over between
+    /// runs of the same session.
+    pub fn start_run(&mut self) {
+        self.timer = 0.0;
+        self.last = Snapshot::default();
+    }
+
+    pub fn record_death(&mut self, now: f64) {
+        self.death_times.push(now);
+    }
+
+    pub fn spawn_scale(&self) -> f32 {
+        1.0 + self.level * MAX_SPAWN_SWING
+    }
+
+    pub fn speed_scale(&self) -> f32 {
+        1.0 + self.level * MAX_SPEED_SWING
+    }
+
+    /// Advances the evaluation timer and, when it fires, adjusts the level and
+    /// logs the change to `stats`.
+    pub fn update(&mut self, delta: f32, now: f64, stats: &mut RunStats) -> Option<Adjustment> {
+        self.timer += delta;
+        if self.timer < EVALUATION_INTERVAL {
+            return None;
+        }
+        self.timer -= EVALUATION_INTERVAL;
+
+        let shots = stats.shots_fired - self.last.shots_fired;
+        let hits = stats.shots_hit - self.last.shots_hit;
+        let kills = stats.kills - self.last.kills;
+        let near_misses = stats.near_misses - self.last.near_misses;
+        self.last = Snapshot {
+            shots_fired: stats.shots_fired,
+            shots_hit: stats.shots_hit,
+            kills: stats.kills,
+            near_misses: stats.near_misses,
+        };
+        self.death_times.retain(|&time| now - time < DEATH_MEMORY);
+
+        let accuracy = if shots > 0 {
+            hits as f32 / shots as f32 - TARGET_ACCURACY
+        } else {
+            0.0
+        };
+        let kill_rate = kills as f32 / EVALUATION_INTERVAL / TARGET_KILL_RATE - 1.0;
+        let target = (accuracy + kill_rate.clamp(-1.0, 1.0) * 0.5
+            - (near_misses as f32 * 0.1).min(0.5)
+            - (self.death_times.len() as f32 * 0.2).min(0.6))
+        .clamp(-1.0, 1.0);
+
+        let level = self.level + (target - self.level).clamp(-MAX_STEP, MAX_STEP);
+        if level == self.level {
+            return None;
+        }
+        self.level = level;
+        let adjustment = Adjustment {
+            time: stats.time_survived,
+            level,
+            spawn_scale: self.spawn_scale(),
+            speed_scale: self.speed_scale(),
+        };
+        stats.adjustments.push(adjustment);
+        Some(adjustment)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays one evaluation interval ending at `now` with the given shots,
+    /// hits, kills and near misses.
+    fn play(
+        adaptive: &mut Adaptive,
+        stats: &mut RunStats,
+        now: f64,
+        [shots, hits, kills, near_misses]: [u32; 4],
+    ) -> Option<Adjustment> {
+        stats.time_survived += EVALUATION_INTERVAL;
+        stats.shots_fired += shots;
+        stats.shots_hit += hits;
+        stats.kills += kills;
+        stats.near_misses += near_misses;
+        adaptive.update(EVALUATION_INTERVAL, now, stats)
+    }
+
+    const SHARP: [u32; 4] = [10, 10, 15, 0];
+    const STRUGGLING: [u32; 4] = [10, 0, 0, 10];
+
+    #[test]
+    fn waits_for_a_full_interval() {
+        let mut adaptive = Adaptive::new();
+        let mut stats = RunStats {
+            shots_fired: 10,
+            shots_hit: 10,
+            kills: 15,
+            ..RunStats::default()
+        };
+        assert!(adaptive
+            .update(EVALUATION_INTERVAL - 0.5, 0.0, &mut stats)
+            .is_none());
+        assert!(adaptive.update(0.5, 0.0, &mut stats).is_some());
+    }
+
+    #[test]
+    fn ramps_up_one_step_at_a_time_and_logs_each_step() {
+        let mut adaptive = Adaptive::new();
+        let mut stats = RunStats::default();
+        let mut levels = Vec::new();
+        for evaluation in 0..3 {
+            let adjustment = play(&mut adaptive, &mut stats, evaluation as f64 * 5.0, SHARP)
+                .expect("a sharp player raises the level");
+            levels.push(adjustment.level);
+        }
+        for (level, expected) in levels.iter().zip([0.1, 0.2, 0.3]) {
+            assert!((level - expected).abs() < 1e-6, "{:?}", levels);
+        }
+        assert_eq!(stats.adjustments.len(), 3);
+        assert_eq!(stats.adjustments[2].time, 15.0);
+        assert!(adaptive.spawn_scale() > 1.0 && adaptive.speed_scale() > 1.0);
+    }
+
+    #[test]
+    fn clamps_at_the_top() {
+        let mut adaptive = Adaptive::new();
+        let mut stats = RunStats::default();
+        for evaluation in 0..30 {
+            play(&mut adaptive, &mut stats, evaluation as f64 * 5.0, SHARP);
+        }
+        assert_eq!(adaptive.spawn_scale(), 1.0 + MAX_SPAWN_SWING);
+        assert_eq!(adaptive.speed_scale(), 1.0 + MAX_SPEED_SWING);
+        // Already at the top, so nothing changes or gets logged.
+        let logged = stats.adjustments.len();
+        assert!(play(&mut adaptive, &mut stats, 150.0, SHARP).is_none());
+        assert_eq!(stats.adjustments.len(), logged);
+    }
+
+    #[test]
+    fn clamps_at_the_bottom() {
+        let mut adaptive = Adaptive::new();
+        let mut stats = RunStats::default();
+        for death in 0..3 {
+            adaptive.record_death(death as f64);
+        }
+        for evaluation in 0..30 {
+            play(
+                &mut adaptive,
+                &mut stats,
+                evaluation as f64 * 5.0,
+                STRUGGLING,
+            );
+        }
+        assert_eq!(adaptive.spawn_scale(), 1.0 - MAX_SPAWN_SWING);
+        assert_eq!(adaptive.speed_scale(), 1.0 - MAX_SPEED_SWING);
+    }
+
+    #[test]
+    fn recent_deaths_pull_the_level_down_until_forgotten() {
+        let mut adaptive = Adaptive::new();
+        let mut stats = RunStats::default();
+        // Close to the target accuracy and kill rate, aiming just below 0.
+        let steady = [10, 5, 7, 0];
+        let before = play(&mut adaptive, &mut stats, 0.0, steady).unwrap().level;
+        assert!(before > -MAX_STEP / 2.0, "{}", before);
+
+        adaptive.record_death(0.0);
+        let adjustment = play(&mut adaptive, &mut stats, 5.0, steady).unwrap();
+        assert!(
+            adjustment.level < before - MAX_STEP / 2.0,
+            "{:?}",
+            adjustment
+        );
+
+        let forgotten = DEATH_MEMORY + 5.0;
+        let adjustment = play(&mut adaptive, &mut stats, forgotten, steady).unwrap();
+        assert!((adjustment.level - before).abs() < 1e-6, "{:?}", adjustment);
+    }
+
+    #[test]
+    fn the_level_carries_over_between_runs() {
+        let mut adaptive = Adaptive::new();
+        let mut stats = RunStats::default();
+        play(&mut adaptive, &mut stats, 0.0, SHARP);
+        adaptive.start_run();
+        let mut stats = RunStats::default();
+        let adjustment = play(&mut adaptive, &mut stats, 5.0, SHARP).unwrap();
+        assert!((adjustment.level - 0.2).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,347 @@
+//! Headless batch simulation for balance tuning.
+//!
+//! Plays one run per seed with a bot at the controls, as fast as the machine
+//! allows and without opening a window, and prints one line of statistics per
+//! run, in seed order:
+//!
+//!     cargo run --release --bin simulate -- --seeds 1..1000 --bot basic --difficulty normal
+//!
+//! Options:
+//!
+//! - `--seeds A..B`, `A..=B` or a single seed, at least one seed in order and
+//!   at most 100000 seeds. Defaults to `1..101`.
+//! - `--bot basic|idle`. `idle` never moves or fires, as a baseline.
+//! - `--difficulty easy|normal|hard|insane`.
+//! - `--minutes M`: runs still going after this much game time are cut off and
+//!   reported as `survived`. Defaults to 10.
+//! - `--format csv|json`. JSON is one object per run, in an array.
+//! - `--tuning PATH`: the tuning file to simulate with. Defaults to
+//!   `assets/tuning.toml`, or the built-in tuning if there is none.
+
+use macroquad::prelude::*;
+use my_game::bot::Bot;
+use my_game::difficulty::Preset;
+use my_game::ecs::Entity;
+use my_game::enemy::EnemyKind;
+use my_game::events::Event;
+use my_game::pool::PoolCaps;
+use my_game::tuning::{Tuning, TUNING_PATH};
+use my_game::world::{Controller, Input, SimParams, World};
+use std::fs;
+use std::ops::Range;
+use std::process;
+use std::thread;
+
+/// The arena of the default 800x600 window.
+const ARENA: Vec2 = vec2(800.0, 600.0);
+/// Fixed step, as at 60 FPS.
+const DELTA: f32 = 1.0 / 60.0;
+/// The most seeds one batch runs. Every outcome is kept until the batch is
+/// printed, so a range like `1..18446744073709551615` is refused up front
+/// rather than run until memory runs out.
+const MAX_SEEDS: u64 = 100_000;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum BotKind {
+    Basic,
+    Idle,
+}
+
+impl BotKind {
+    fn from_key(key: &str) -> Option<BotKind> {
+        match key {
+            "basic" => Some(BotKind::Basic),
+            "idle" => Some(BotKind::Idle),
+            _ => None,
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            BotKind::Basic => "basic",
+            BotKind::Idle => "idle",
+        }
+    }
+
+    fn controller(&self) -> Box<dyn Controller> {
+        match self {
+            BotKind::Basic => Box::new(Bot::default()),
+            BotKind::Idle => Box::new(Idle),
+        }
+    }
+}
+
+/// Sits still and never fires.
+struct Idle;
+
+impl Controller for Idle {
+    fn input(&mut self, _world: &World, _ship: Entity) -> Input {
+        Input::default()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+struct Options {
+    seeds: Range<u64>,
+    bot: BotKind,
+    preset: Preset,
+    minutes: f32,
+    format: Format,
+    tuning: Tuning,
+}
+
+/// How one run went.
+struct Outcome {
+    seed: u64,
+    time_survived: f32,
+    score: u32,
+    kills: [u32; 3],
+    shots_fired: u32,
+    accuracy: f32,
+    /// The enemy kind the ship died to, `shot` for a friendly shot, or
+    /// `survived` when the time limit ran out first.
+    cause: &'static str,
+}
+
+/// `None` for anything but at least one seed in order, and for more than
+/// [`MAX_SEEDS`] seeds. An inclusive range up to `u64::MAX` can't be written
+/// as a `Range`, so it is refused too.
+fn parse_seeds(text: &str) -> Option<Range<u64>> {
+    let seeds = if let Some((start, end)) = text.split_once("..=") {
+        start.parse().ok()?..end.parse::<u64>().ok()?.checked_add(1)?
+    } else if let Some((start, end)) = text.split_once("..") {
+        start.parse().ok()?..end.parse().ok()?
+    } else {
+        let seed: u64 = text.parse().ok()?;
+        seed..seed.checked_add(1)?
+    };
+    (!seeds.is_empty() && seeds.end - seeds.start <= MAX_SEEDS).then_some(seeds)
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        seeds: 1..101,
+        bot: BotKind::Basic,
+        preset: Preset::Normal,
+        minutes: 10.0,
+        format: Format::Csv,
+        tuning: Tuning::default(),
+    };
+    let mut tuning_path = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", flag))?;
+        match flag.as_str() {
+            "--seeds" => {
+                options.seeds = parse_seeds(&value).ok_or_else(|| {
+                    format!("bad seed range {} (at most {} seeds)", value, MAX_SEEDS)
+                })?;
+            }
+            "--bot" => {
+                options.bot =
+                    BotKind::from_key(&value).ok_or_else(|| format!("unknown bot {}", value))?;
+            }
+            "--difficulty" => {
+                options.preset = Preset::from_key(&value)
+                    .ok_or_else(|| format!("unknown difficulty {}", value))?;
+            }
+            "--minutes" => {
+                options.minutes = value
+                    .parse()
+                    .ok()
+                    .filter(|minutes: &f32| *minutes > 0.0)
+                    .ok_or_else(|| format!("bad minutes {}", value))?;
+            }
+            "--format" => {
+                options.format = match value.as_str() {
+                    "csv" => Format::Csv,
+                    "json" => Format::Json,
+                    _ => return Err(format!("unknown format {}", value)),
+                };
+            }
+            "--tuning" => tuning_path = Some(value),
+            _ => return Err(format!("unknown option {}", flag)),
+        }
+    }
+    let text = match tuning_path {
+        Some(path) => {
+            Some(fs::read_to_string(&path).map_err(|error| format!("{}: {}", path, error))?)
+        }
+        None => fs::read_to_string(TUNING_PATH).ok(),
+    };
+    if let Some(text) = text {
+        options.tuning = Tuning::parse(&text).map_err(|error| error.to_string())?;
+    }
+    Ok(options)
+}
+
+fn simulate(options: &Options, seed: u64) -> Outcome {
+    let mut params = SimParams::default();
+    options.tuning.apply(&mut params);
+    let mut world = World::new(
+        ARENA,
+        options.preset.difficulty(),
+        PoolCaps::default(),
+        seed,
+        params,
+    );
+    let mut controller = options.bot.controller();
+    let ship = world.ships[0];
+    let limit = options.minutes * 60.0;
+    let mut cause = "survived";
+    while !world.game_over && world.stats.time_survived < limit {
+        let input = controller.input(&world, ship);
+        for event in world.step(&[input], DELTA).iter() {
+            if let Event::PlayerDied { by, .. } = event {
+                cause = by.map_or("shot", |kind| kind.key());
+            }
+        }
+    }
+    let stats = &world.stats;
+    Outcome {
+        seed,
+        time_survived: stats.time_survived,
+        score: world.score,
+        kills: EnemyKind::ALL.map(|kind| stats.kills_of(kind)),
+        shots_fired: stats.shots_fired,
+        accuracy: stats.accuracy(),
+        cause,
+    }
+}
+
+/// Simulates every seed, spread over the available cores, in seed order.
+fn simulate_all(options: &Options) -> Vec<Outcome> {
+    let Range { start, end } = options.seeds;
+    let threads = thread::available_parallelism().map_or(1, |count| count.get() as u64);
+    let chunk_size = (end - start).div_ceil(threads).max(1);
+    thread::scope(|scope| {
+        let workers: Vec<_> = (start..end)
+            .step_by(chunk_size as usize)
+            .map(|chunk_start| {
+                let chunk = chunk_start..chunk_start.saturating_add(chunk_size).min(end);
+                scope.spawn(move || {
+                    chunk
+                        .map(|seed| simulate(options, seed))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("a simulation thread panicked"))
+            .collect()
+    })
+}
+
+fn print_csv(options: &Options, outcomes: &[Outcome]) {
+    println!(
+        "seed,difficulty,bot,time_survived,score,kills_small,kills_medium,kills_big,shots_fired,accuracy,cause_of_death"
+    );
+    for outcome in outcomes {
+        println!(
+            "{},{},{},{:.2},{},{},{},{},{},{:.3},{}",
+            outcome.seed,
+            options.preset.key(),
+            options.bot.key(),
+            outcome.time_survived,
+            outcome.score,
+            outcome.kills[0],
+            outcome.kills[1],
+            outcome.kills[2],
+            outcome.shots_fired,
+            outcome.accuracy,
+            outcome.cause
+        );
+    }
+}
+
+fn print_json(options: &Options, outcomes: &[Outcome]) {
+    println!("[");
+    for (index, outcome) in outcomes.iter().enumerate() {
+        let separator = if index + 1 < outcomes.len() { "," } else { "" };
+        println!(
+            "  {{\"seed\": {}, \"difficulty\": \"{}\", \"bot\": \"{}\", \"time_survived\": {:.2}, \"score\": {}, \"kills\": {{\"small\": {}, \"medium\": {}, \"big\": {}}}, \"shots_fired\": {}, \"accuracy\": {:.3}, \"cause_of_death\": \"{}\"}}{}",
+            outcome.seed,
+            options.preset.key(),
+            options.bot.key(),
+            outcome.time_survived,
+            outcome.score,
+            outcome.kills[0],
+            outcome.kills[1],
+            outcome.kills[2],
+            outcome.shots_fired,
+            outcome.accuracy,
+            outcome.cause,
+            separator
+        );
+    }
+    println!("]");
+}
+
+fn main() {
+    let options = parse_args().unwrap_or_else(|error| {
+        eprintln!("simulate: {}", error);
+        eprintln!(
+            "usage: simulate [--seeds A..B] [--bot basic|idle] [--difficulty easy|normal|hard|insane] [--minutes M] [--format csv|json] [--tuning PATH]"
+        );
+        process::exit(2);
+    });
+    let outcomes = simulate_all(&options);
+    match options.format {
+        Format::Csv => print_csv(&options, &outcomes),
+        Format::Json => print_json(&options, &outcomes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ranges_and_single_seeds() {
+        assert_eq!(parse_seeds("1..101"), Some(1..101));
+        assert_eq!(parse_seeds("1..=100"), Some(1..101));
+        assert_eq!(parse_seeds("7"), Some(7..8));
+        assert_eq!(parse_seeds("5..=5"), Some(5..6));
+    }
+
+    #[test]
+    fn empty_and_reversed_ranges_are_refused() {
+        assert_eq!(parse_seeds("5..5"), None);
+        assert_eq!(parse_seeds("5..1"), None);
+        assert_eq!(parse_seeds("5..=1"), None);
+    }
+
+    #[test]
+    fn ranges_past_the_last_seed_are_refused() {
+        assert_eq!(parse_seeds("1..=18446744073709551615"), None);
+        assert_eq!(parse_seeds("18446744073709551615"), None);
+    }
+
+    #[test]
+    fn ranges_over_the_maximum_are_refused() {
+        assert_eq!(parse_seeds("1..=100000"), Some(1..100_001));
+        assert_eq!(parse_seeds("0..100000"), Some(0..100_000));
+        assert_eq!(parse_seeds("1..=100001"), None);
+        assert_eq!(parse_seeds("0..100001"), None);
+        assert_eq!(parse_seeds("1..18446744073709551615"), None);
+        assert_eq!(
+            parse_seeds("18446744073709551614..18446744073709551615"),
+            Some(u64::MAX - 1..u64::MAX)
+        );
+    }
+
+    #[test]
+    fn garbage_is_refused() {
+        assert_eq!(parse_seeds(""), None);
+        assert_eq!(parse_seeds("a..b"), None);
+        assert_eq!(parse_seeds("-1..5"), None);
+    }
+}
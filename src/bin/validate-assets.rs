@@ -0,0 +1,171 @@
+//! Checks the asset manifest against the files it names:
+//!
+//!     cargo run --bin validate-assets
+//!
+//! Every key the game asks for must be in the manifest, and every file the
+//! manifest names must exist. Images and sprite sheets must decode, sheets must
+//! divide evenly into frames with every animation on the sheet, and sheets
+//! the simulation collides must keep the frame their collider was drawn for.
+//! Sounds must decode all the way through. Fonts are only checked to exist.
+//!
+//! Prints every problem found and exits with status 1 if there were any. An
+//! assets folder other than `assets` can be given as the only argument.
+
+use macroquad::math::Vec2;
+use macroquad::texture::Image;
+use my_game::manifest::{Manifest, ManifestError, SpriteEntry, MANIFEST_FILE};
+use my_game::resources::check_sound;
+use my_game::sprites;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Problems found so far, each under the manifest entry it is about.
+struct Report {
+    folder: PathBuf,
+    problems: Vec<String>,
+}
+
+impl Report {
+    fn problem(&mut self, entry: &str, message: impl AsRef<str>) {
+        self.problems
+            .push(format!("{}: {}", entry, message.as_ref()));
+    }
+
+    /// The file's contents, or `None` after reporting why it can't be read.
+    fn read(&mut self, entry: &str, file: &str) -> Option<Vec<u8>> {
+        match fs::read(self.folder.join(file)) {
+            Ok(bytes) => Some(bytes),
+            Err(error) => {
+                self.problem(entry, format!("{}: {}", file, error));
+                None
+            }
+        }
+    }
+
+    fn image(&mut self, entry: &str, file: &str) -> Option<Image> {
+        let bytes = self.read(entry, file)?;
+        match Image::from_file_with_format(&bytes, None) {
+            Ok(image) => Some(image),
+            Err(error) => {
+                self.problem(entry, format!("{}: {}", file, error));
+                None
+            }
+        }
+    }
+
+    fn sprite(&mut self, key: &str, sprite: &SpriteEntry) {
+        let entry = format!("sprites.{}", key);
+        let Some(image) = self.image(&entry, &sprite.file) else {
+            return;
+        };
+        let (width, height) = (image.width as u32, image.height as u32);
+        let [frame_width, frame_height] = sprite.frame;
+        if width % frame_width != 0 || height % frame_height != 0 {
+            self.problem(
+                &entry,
+                format!(
+                    "{} is {}x{}, which doesn't divide into {}x{} frames",
+                    sprite.file, width, height, frame_width, frame_height
+                ),
+            );
+        }
+        let (columns, rows) = (width / frame_width, height / frame_height);
+        for animation in &sprite.animations {
+            if animation.row >= rows || animation.frames > columns {
+                self.problem(
+                    &entry,
+                    format!(
+                        "animation {} needs {} frames on row {}, but the sheet has {} rows of {}",
+                        animation.name, animation.frames, animation.row, rows, columns
+                    ),
+                );
+            }
+        }
+        if let Some(def) = sprites::ALL.iter().find(|def| def.key == key) {
+            let frame = sprite.frame_size();
+            let bounds = def.collider.bounds(Vec2::ZERO);
+            let (min, max) = (bounds.point(), bounds.point() + bounds.size());
+            if min.cmplt(-frame / 2.0).any() || max.cmpgt(frame / 2.0).any() {
+                self.problem(
+                    &entry,
+                    format!(
+                        "its collider doesn't fit in a {}x{} frame",
+                        frame_width, frame_height
+                    ),
+                );
+            }
+            let drawn_for = def.frame_size();
+            if drawn_for != sprite.frame_size() {
+                self.problem(
+                    &entry,
+                    format!(
+                        "frame is {}x{}, but its collider is drawn for {}x{}",
+                        frame_width, frame_height, drawn_for.x, drawn_for.y
+                    ),
+                );
+            }
+        }
+    }
+
+    fn sound(&mut self, key: &str, file: &str) {
+        let entry = format!("sounds.{}", key);
+        let Some(bytes) = self.read(&entry, file) else {
+            return;
+        };
+        if let Err(reason) = check_sound(&bytes) {
+            self.problem(&entry, format!("{}: {}", file, reason));
+        }
+    }
+}
+
+fn main() {
+    let folder = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "assets".to_string());
+    let folder = Path::new(&folder);
+    let manifest_path = folder.join(MANIFEST_FILE);
+    let manifest = fs::read_to_string(&manifest_path)
+        .map_err(|error| format!("{}: {}", manifest_path.display(), error))
+        .and_then(|text| Manifest::parse(&text).map_err(|error| error.to_string()))
+        .unwrap_or_else(|error| {
+            eprintln!("validate-assets: {}", error);
+            process::exit(1);
+        });
+
+    let mut report = Report {
+        folder: folder.to_path_buf(),
+        problems: Vec::new(),
+    };
+    for missing in manifest.missing() {
+        if let ManifestError::Missing { section, key } = missing {
+            report.problem(&format!("{}.{}", section, key), "missing");
+        }
+    }
+    for (key, sprite) in &manifest.sprites {
+        report.sprite(key, sprite);
+    }
+    for (key, file) in &manifest.images {
+        report.image(&format!("images.{}", key), file);
+    }
+    for (key, file) in &manifest.sounds {
+        report.sound(key, file);
+    }
+    for (key, file) in &manifest.fonts {
+        report.read(&format!("fonts.{}", key), file);
+    }
+
+    let entries = manifest.sprites.len()
+        + manifest.images.len()
+        + manifest.sounds.len()
+        + manifest.fonts.len();
+    if report.problems.is_empty() {
+        println!("{} assets OK", entries);
+        return;
+    }
+    for problem in &report.problems {
+        eprintln!("{}", problem);
+    }
+    eprintln!("{} problems in {} assets", report.problems.len(), entries);
+    process::exit(1);
+}
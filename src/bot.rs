@@ -0,0 +1,231 @@
+//! An autopilot that flies a ship through the same [`Controller`] interface
+//! as the keyboard.
+//!
+//! Every step the bot tries the nine ways the ship can move, follows each for
+//! a short lookahead against where the enemies will be by then, and takes the
+//! one that keeps the ship furthest from trouble, breaking ties by lining up
+//! under a target. It fires whenever an enemy is straight ahead. It only reads
+//! the world, so it plays headless as well as on screen, and it is
+//! deterministic: the same world always gets the same input.
+
+use crate::ecs::Entity;
+use crate::world::{Controller, Input, World};
+use macroquad::prelude::*;
+
+/// Points in time, spread over the lookahead, each candidate move is checked
+/// at.
+const SAMPLES: usize = 6;
+/// Clearance beyond which enemies are no concern at all.
+const SAFE_CLEARANCE: f32 = 48.0;
+/// How far off to the side of an enemy a shot is still worth taking.
+const FIRE_MARGIN: f32 = 8.0;
+/// How far down the arena, as a fraction of its height, the bot likes to fly.
+const PREFERRED_HEIGHT: f32 = 0.8;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Bot {
+    /// Seconds ahead each candidate move is followed.
+    pub lookahead: f32,
+}
+
+impl Default for Bot {
+    fn default() -> Self {
+        Bot { lookahead: 0.6 }
+    }
+}
+
+impl Bot {
+    /// The smallest gap between the ship and any enemy while the ship moves at
+    /// `vel` for the lookahead, negative if they would collide.
+    fn clearance(&self, world: &World, pos: Vec2, radius: f32, vel: Vec2) -> f32 {
+        let mut clearance = f32::MAX;
+        for step in 0..=SAMPLES {
+            let time = self.lookahead * step as f32 / SAMPLES as f32;
+            let ship_pos = (pos + vel * time).clamp(Vec2::ZERO, world.arena);
+            for (enemy, _) in world.enemies.iter() {
+                let (Some(transform), Some(velocity), Some(body)) = (
+                    world.transforms.get(enemy),
+                    world.velocities.get(enemy),
+                    world.bodies.get(enemy),
+                ) else {
+                    continue;
+                };
+                let enemy_pos = transform.pos + velocity.vel * time;
+                let gap = ship_pos.distance(enemy_pos) - radius - body.size / 2.0;
+                clearance = clearance.min(gap);
+            }
+        }
+        clearance
+    }
+
+    /// The x position of the closest enemy ahead of the ship to line up under.
+    fn target(&self, world: &World, pos: Vec2) -> Option<f32> {
+        world
+            .enemies
+            .iter()
+            .filter_map(|(enemy, _)| world.transforms.get(enemy))
+            .filter(|transform| transform.pos.y < pos.y && transform.pos.y > 0.0)
+            .map(|transform| transform.pos.x)
+            .min_by(|a, b| (a - pos.x).abs().total_cmp(&(b - pos.x).abs()))
+    }
+
+    /// Whether a shot fired now would fly into an enemy.
+    fn lined_up(&self, world: &World, pos: Vec2) -> bool {
+        world.enemies.iter().any(|(enemy, _)| {
+            let (Some(transform), Some(body)) =
+                (world.transforms.get(enemy), world.bodies.get(enemy))
+            else {
+                return false;
+            };
+            transform.pos.y < pos.y
+                && (transform.pos.x - pos.x).abs() < body.size / 2.0 + FIRE_MARGIN
+        })
+    }
+}
+
+impl Controller for Bot {
+    fn input(&mut self, world: &World, ship: Entity) -> Input {
+        let (Some(player), Some(body), true) = (
+            world.players.get(ship),
+            world.bodies.get(ship),
+            world.ship_alive(ship),
+        ) else {
+            return Input::default();
+        };
+        let pos = world.ship_pos(ship);
+        let bounds = body.bounds(pos);
+        let radius = bounds.w.max(bounds.h) / 2.0;
+        let target_x = self.target(world, pos).unwrap_or(world.arena.x / 2.0);
+        let preferred_y = world.arena.y * PREFERRED_HEIGHT;
+
+        let mut best = (f32::MIN, Vec2::ZERO);
+        for y in [-1.0, 0.0, 1.0] {
+            for x in [-1.0, 0.0, 1.0] {
+                let movement = vec2(x, y);
+                let vel = movement * player.speed;
+                let end = (pos + vel * self.lookahead).clamp(Vec2::ZERO, world.arena);
+                let safety = self.clearance(world, pos, radius, vel).min(SAFE_CLEARANCE);
+                // Safety first: across the whole arena, placement is worth
+                // about a pixel of clearance, so it only decides between
+                // moves that are about as safe.
+                let placement =
+                    (end.x - target_x).abs() * 0.001 + (end.y - preferred_y).abs() * 0.0005;
+                let score = safety - placement;
+                if score > best.0 {
+                    best = (score, movement);
+                }
+            }
+        }
+
+        let mut movement = best.1;
+        // Steer as if the controls were not mirrored.
+        if world.params.mirror_controls {
+            movement.x = -movement.x;
+        }
+        Input {
+            movement,
+            fire: self.lined_up(world, pos),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::{Difficulty, Preset};
+    use crate::events::Event;
+    use crate::pool::PoolCaps;
+    use crate::world::SimParams;
+    use std::collections::VecDeque;
+
+    const DELTA: f32 = 1.0 / 60.0;
+    const ARENA: Vec2 = vec2(800.0, 600.0);
+    /// Simulated time per preset and seed, over as many runs as it takes.
+    const MINUTES: f32 = 3.0;
+    /// The longest an explosion lives: gameplay's emitter lifetime plus its
+    /// randomness.
+    const EXPLOSION_SECONDS: f32 = 0.6 * 1.3;
+
+    /// Most bullets one ship can have in the air: one per cooldown for as
+    /// long as a bullet takes to cross the arena.
+    fn bullet_bound(params: &SimParams) -> usize {
+        (ARENA.y / params.bullet_speed / params.fire_cooldown).ceil() as usize + 1
+    }
+
+    /// Twice as many enemies as the fastest spawn rate keeps alive for as
+    /// long as the slowest, largest enemy takes to cross the arena, twice
+    /// over to allow for streaks of spawns.
+    fn enemy_bound(difficulty: &Difficulty, params: &SimParams) -> usize {
+        let rate = difficulty.spawn_rate.start.max(difficulty.spawn_rate.end);
+        let slowest = difficulty.speed_min.start.min(difficulty.speed_min.end);
+        let largest = params.enemy_sizes[2][1];
+        (2.0 * rate * (ARENA.y + largest) / slowest).ceil() as usize
+    }
+
+    /// Every explosion comes from a bullet, so no more can be live at once
+    /// than the bullets in the air plus those fired while one burns out.
+    fn explosion_bound(params: &SimParams) -> usize {
+        let fired = (EXPLOSION_SECONDS / params.fire_cooldown).ceil() as usize;
+        (bullet_bound(params) + fired) * params.bullet_pierce as usize
+    }
+
+    #[test]
+    fn plays_for_minutes_within_bounds() {
+        let params = SimParams::default();
+        for preset in [Preset::Normal, Preset::Insane] {
+            let difficulty = preset.difficulty();
+            let (bullets, enemies, explosions) = (
+                bullet_bound(&params),
+                enemy_bound(&difficulty, &params),
+                explosion_bound(&params),
+            );
+            for seed in 1..=2 {
+                let mut runs = 0;
+                let mut current = None;
+                let mut bot = Bot::default();
+                // When each live explosion started, in seconds.
+                let mut burning = VecDeque::new();
+                let steps = (MINUTES * 60.0 / DELTA) as u32;
+                for step in 0..steps {
+                    if current.as_ref().is_none_or(|world: &World| world.game_over) {
+                        runs += 1;
+                        let seed = seed * 100 + runs;
+                        current = Some(World::new(
+                            ARENA,
+                            difficulty,
+                            PoolCaps::default(),
+                            seed,
+                            params,
+                        ));
+                    }
+                    let world = current.as_mut().unwrap();
+                    let now = step as f32 * DELTA;
+                    let input = bot.input(world, world.ships[0]);
+                    for event in world.step(&[input], DELTA).iter() {
+                        if let Event::EnemyKilled { .. } = event {
+                            burning.push_back(now);
+                        }
+                    }
+                    while burning
+                        .front()
+                        .is_some_and(|&start| now - start > EXPLOSION_SECONDS)
+                    {
+                        burning.pop_front();
+                    }
+                    let context = format!("{} seed {} at {:.1}s", preset.key(), seed, now);
+                    assert!(world.projectiles.len() <= bullets, "bullets, {}", context);
+                    assert!(world.enemies.len() <= enemies, "enemies, {}", context);
+                    assert!(burning.len() <= explosions, "explosions, {}", context);
+                }
+                // The bot should last a while, not die straight away each time.
+                assert!(
+                    (runs as f32) < MINUTES * 6.0,
+                    "{} seed {} took {} runs",
+                    preset.key(),
+                    seed,
+                    runs
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Uniform grid broadphase.
+//!
+//! Rebuilt every frame: one set of rectangles (usually enemies) is inserted,
+//! then any number of other rectangles are tested against it with [`Grid::query`].
+//! Only entries sharing a cell are returned, so callers still run the exact
+//! overlap test on the candidates.
+
+use macroquad::prelude::Rect;
+
+pub struct Grid {
+    cell_size: f32,
+    origin_x: f32,
+    origin_y: f32,
+    columns: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    pub fn new(cell_size: f32) -> Self {
+        Grid {
+            cell_size,
+            origin_x: 0.0,
+            origin_y: 0.0,
+            columns: 1,
+            rows: 1,
+            cells: vec![vec![]],
+        }
+    }
+
+    /// Empties the grid and resizes it to cover `bounds`. Entries outside the
+    /// bounds are clamped into the border cells, so nothing is ever lost.
+    pub fn reset(&mut self, bounds: Rect) {
+        self.origin_x = bounds.x;
+        self.origin_y = bounds.y;
+        self.columns = ((bounds.w / self.cell_size).ceil() as usize).max(1);
+        self.rows = ((bounds.h / self.cell_size).ceil() as usize).max(1);
+        let len = self.columns * self.rows;
+        self.cells.resize_with(len, Vec::new);
+        self.cells.truncate(len);
+        for cell in &mut self.cells {
+            cell.clear();
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, rect: Rect) {
+        let (x0, y0, x1, y1) = self.cell_range(rect);
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                self.cells[y * self.columns + x].push(index);
+            }
+        }
+    }
+
+    /// Fills `out` with the sorted, deduplicated indices of every entry that
+    /// shares a cell with `rect`.
+    pub fn query(&self, rect: Rect, out: &mut Vec<usize>) {
+        out.clear();
+        let (x0, y0, x1, y1) = self.cell_range(rect);
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                out.extend_from_slice(&self.cells[y * self.columns + x]);
+            }
+        }
+        out.sort_unstable();
+        out.dedup();
+    }
+
+    fn cell_range(&self, rect: Rect) -> (usize, usize, usize, usize) {
+        let cell = |value: f32, origin: f32, count: usize| {
+            (((value - origin) / self.cell_size).floor().max(0.0) as usize).min(count - 1)
+        };
+        (
+            cell(rect.x, self.origin_x, self.columns),
+            cell(rect.y, self.origin_y, self.rows),
+            cell(rect.x + rect.w, self.origin_x, self.columns),
+            cell(rect.y + rect.h, self.origin_y, self.rows),
+        )
+    }
+}
@@ -0,0 +1,386 @@
+//! Narrowphase colliders.
+//!
+//! Every collider is treated as a convex core (a point, a segment or a
+//! polygon) inflated by a radius: a circle is a point plus radius, a capsule a
+//! segment plus radius, and boxes and polygons have no radius. Two colliders
+//! intersect when the distance between their cores is at most the sum of
+//! their radii, which handles every pair of kinds with the same code.
+//!
+//! Colliders are `Copy` and never allocate; polygons hold up to
+//! [`MAX_POLYGON_POINTS`] vertices in counter-clockwise or clockwise order.
+
+use macroquad::prelude::*;
+
+pub const MAX_POLYGON_POINTS: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Collider {
+    Circle {
+        radius: f32,
+    },
+    Aabb {
+        half_extents: Vec2,
+    },
+    Capsule {
+        a: Vec2,
+        b: Vec2,
+        radius: f32,
+    },
+    Polygon {
+        points: [Vec2; MAX_POLYGON_POINTS],
+        len: usize,
+    },
+}
+
+impl Collider {
+    /// Builds a convex polygon collider. Points past [`MAX_POLYGON_POINTS`]
+    /// are ignored.
+    pub const fn polygon(source: &[Vec2]) -> Collider {
+        let mut points = [Vec2::ZERO; MAX_POLYGON_POINTS];
+        let mut len = 0;
+        while len < source.len() && len < MAX_POLYGON_POINTS {
+            points[len] = source[len];
+            len += 1;
+        }
+        Collider::Polygon { points, len }
+    }
+
+    /// Scales the collider around its origin. Radii use the smaller axis so
+    /// a stretched circle never grows past the sprite.
+    pub fn scaled(&self, scale: Vec2) -> Collider {
+        let radius_scale = scale.x.min(scale.y);
+        match *self {
+            Collider::Circle { radius } => Collider::Circle {
+                radius: radius * radius_scale,
+            },
+            Collider::Aabb { half_extents } => Collider::Aabb {
+                half_extents: half_extents * scale,
+            },
+            Collider::Capsule { a, b, radius } => Collider::Capsule {
+                a: a * scale,
+                b: b * scale,
+                radius: radius * radius_scale,
+            },
+            Collider::Polygon { mut points, len } => {
+                for point in &mut points[..len] {
+                    *point *= scale;
+                }
+                Collider::Polygon { points, len }
+            }
+        }
+    }
+
+    /// World-space bounding box, used by the broadphase.
+    pub fn bounds(&self, pos: Vec2) -> Rect {
+        let (core, radius) = self.core(pos);
+        let points = core.points();
+        let mut min = points[0];
+        let mut max = points[0];
+        for point in &points[1..] {
+            min = min.min(*point);
+            max = max.max(*point);
+        }
+        min -= Vec2::splat(radius);
+        max += Vec2::splat(radius);
+        Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+    }
+
+    pub fn intersects(&self, pos: Vec2, other: &Collider, other_pos: Vec2) -> bool {
+        let (core, radius) = self.core(pos);
+        let (other_core, other_radius) = other.core(other_pos);
+        core.distance(&other_core) <= radius + other_radius
+    }
+
+    fn core(&self, pos: Vec2) -> (Core, f32) {
+        match *self {
+            Collider::Circle { radius } => (Core::new(&[pos]), radius),
+            Collider::Aabb { half_extents } => {
+                let (x, y) = (half_extents.x, half_extents.y);
+                let corners = [vec2(-x, -y), vec2(x, -y), vec2(x, y), vec2(-x, y)];
+                (Core::new(&corners.map(|corner| pos + corner)), 0.0)
+            }
+            Collider::Capsule { a, b, radius } => (Core::new(&[pos + a, pos + b]), radius),
+            Collider::Polygon { points, len } => {
+                let mut core = Core::new(&points[..len]);
+                for point in core.points_mut() {
+                    *point += pos;
+                }
+                (core, 0.0)
+            }
+        }
+    }
+}
+
+/// A point, segment or convex polygon in world space.
+struct Core {
+    points: [Vec2; MAX_POLYGON_POINTS],
+    len: usize,
+}
+
+impl Core {
+    fn new(source: &[Vec2]) -> Core {
+        let mut points = [Vec2::ZERO; MAX_POLYGON_POINTS];
+        points[..source.len()].copy_from_slice(source);
+        Core {
+            points,
+            len: source.len(),
+        }
+    }
+
+    fn points(&self) -> &[Vec2] {
+        &self.points[..self.len]
+    }
+
+    fn points_mut(&mut self) -> &mut [Vec2] {
+        &mut self.points[..self.len]
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        let points = self.points();
+        let count = if points.len() > 2 { points.len() } else { 1 };
+        (0..count).map(move |i| (points[i], points[(i + 1) % points.len()]))
+    }
+
+    fn contains(&self, point: Vec2) -> bool {
+        if self.len < 3 {
+            return false;
+        }
+        let mut sign = 0.0;
+        for (a, b) in self.edges() {
+            let cross = (b - a).perp_dot(point - a);
+            if cross != 0.0 {
+                if sign != 0.0 && cross.signum() != sign {
+                    return false;
+                }
+                sign = cross.signum();
+            }
+        }
+        true
+    }
+
+    fn distance(&self, other: &Core) -> f32 {
+        if other.points().iter().any(|&point| self.contains(point))
+            || self.points().iter().any(|&point| other.contains(point))
+        {
+            return 0.0;
+        }
+        let mut distance = f32::MAX;
+        for (a, b) in self.edges() {
+            for (c, d) in other.edges() {
+                distance = distance.min(segment_distance(a, b, c, d));
+            }
+        }
+        distance
+    }
+}
+
+fn point_segment_distance(point: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let length_squared = ab.length_squared();
+    let t = if length_squared > 0.0 {
+        ((point - a).dot(ab) / length_squared).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    point.distance(a + ab * t)
+}
+
+fn segment_distance(a: Vec2, b: Vec2, c: Vec2, d: Vec2) -> f32 {
+    let ab = b - a;
+    let cd = d - c;
+    let denominator = ab.perp_dot(cd);
+    if denominator != 0.0 {
+        let t = (c - a).perp_dot(cd) / denominator;
+        let u = (c - a).perp_dot(ab) / denominator;
+        if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+            return 0.0;
+        }
+    }
+    point_segment_distance(a, c, d)
+        .min(point_segment_distance(b, c, d))
+        .min(point_segment_distance(c, a, b))
+        .min(point_segment_distance(d, a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macroquad::rand::RandGenerator;
+
+    const CASES: usize = 2000;
+
+    /// A random collider of any kind, about `scale` pixels across.
+    fn random_collider(rng: &RandGenerator, scale: f32) -> Collider {
+        match rng.gen_range(0, 4) {
+            0 => Collider::Circle {
+                radius: rng.gen_range(0.0, scale),
+            },
+            1 => Collider::Aabb {
+                half_extents: vec2(rng.gen_range(0.0, scale), rng.gen_range(0.0, scale)),
+            },
+            2 => Collider::Capsule {
+                a: random_point(rng, scale),
+                b: random_point(rng, scale),
+                radius: rng.gen_range(0.0, scale / 2.0),
+            },
+            _ => random_polygon(rng, scale),
+        }
+    }
+
+    fn random_point(rng: &RandGenerator, scale: f32) -> Vec2 {
+        vec2(rng.gen_range(-scale, scale), rng.gen_range(-scale, scale))
+    }
+
+    /// A convex polygon: points on a circle in angle order.
+    fn random_polygon(rng: &RandGenerator, scale: f32) -> Collider {
+        let len = rng.gen_range(3, MAX_POLYGON_POINTS + 1);
+        let radius = rng.gen_range(scale / 4.0, scale);
+        let mut angles: Vec<f32> = (0..len)
+            .map(|_| rng.gen_range(0.0, std::f32::consts::TAU))
+            .collect();
+        angles.sort_by(f32::total_cmp);
+        let points: Vec<Vec2> = angles
+            .iter()
+            .map(|&angle| Vec2::from_angle(angle) * radius)
+            .collect();
+        Collider::polygon(&points)
+    }
+
+    fn rng(seed: u64) -> RandGenerator {
+        let rng = RandGenerator::new();
+        rng.srand(seed);
+        rng
+    }
+
+    #[test]
+    fn intersection_is_symmetric() {
+        let rng = rng(1);
+        for _ in 0..CASES {
+            let (a, b) = (random_collider(&rng, 20.0), random_collider(&rng, 20.0));
+            let (pos_a, pos_b) = (random_point(&rng, 40.0), random_point(&rng, 40.0));
+            assert_eq!(
+                a.intersects(pos_a, &b, pos_b),
+                b.intersects(pos_b, &a, pos_a),
+                "{:?} at {} and {:?} at {}",
+                a,
+                pos_a,
+                b,
+                pos_b
+            );
+        }
+    }
+
+    #[test]
+    fn every_shape_intersects_itself() {
+        let rng = rng(2);
+        for _ in 0..CASES {
+            let collider = random_collider(&rng, 20.0);
+            let pos = random_point(&rng, 100.0);
+            assert!(
+                collider.intersects(pos, &collider, pos),
+                "{:?} at {}",
+                collider,
+                pos
+            );
+        }
+    }
+
+    #[test]
+    fn disjoint_bounds_never_intersect() {
+        let rng = rng(3);
+        let mut disjoint = 0;
+        for _ in 0..CASES {
+            let (a, b) = (random_collider(&rng, 20.0), random_collider(&rng, 20.0));
+            let (pos_a, pos_b) = (random_point(&rng, 40.0), random_point(&rng, 40.0));
+            if a.bounds(pos_a).overlaps(&b.bounds(pos_b)) {
+                continue;
+            }
+            disjoint += 1;
+            assert!(
+                !a.intersects(pos_a, &b, pos_b),
+                "{:?} at {} and {:?} at {}",
+                a,
+                pos_a,
+                b,
+                pos_b
+            );
+        }
+        assert!(
+            disjoint > CASES / 10,
+            "too few disjoint cases: {}",
+            disjoint
+        );
+    }
+
+    #[test]
+    fn shapes_inside_a_polygon_intersect_it() {
+        let square = Collider::polygon(&[
+            vec2(-50.0, -50.0),
+            vec2(50.0, -50.0),
+            vec2(50.0, 50.0),
+            vec2(-50.0, 50.0),
+        ]);
+        let circle = Collider::Circle { radius: 10.0 };
+        let capsule = Collider::Capsule {
+            a: vec2(-20.0, 0.0),
+            b: vec2(20.0, 0.0),
+            radius: 5.0,
+        };
+        let small = Collider::polygon(&[vec2(-5.0, -5.0), vec2(5.0, -5.0), vec2(0.0, 5.0)]);
+        for inner in [circle, capsule, small] {
+            for offset in [Vec2::ZERO, vec2(15.0, -20.0)] {
+                assert!(square.intersects(Vec2::ZERO, &inner, offset), "{:?}", inner);
+                assert!(inner.intersects(offset, &square, Vec2::ZERO), "{:?}", inner);
+            }
+        }
+    }
+
+    #[test]
+    fn a_polygon_inside_a_circle_intersects_it() {
+        let circle = Collider::Circle { radius: 100.0 };
+        let triangle = Collider::polygon(&[vec2(-5.0, -5.0), vec2(5.0, -5.0), vec2(0.0, 5.0)]);
+        assert!(circle.intersects(Vec2::ZERO, &triangle, vec2(30.0, 30.0)));
+        assert!(triangle.intersects(vec2(30.0, 30.0), &circle, Vec2::ZERO));
+    }
+
+    #[test]
+    fn one_point_shapes() {
+        let dot = Collider::Circle { radius: 0.0 };
+        let single = Collider::polygon(&[vec2(1.0, 1.0)]);
+        let point_capsule = Collider::Capsule {
+            a: Vec2::ZERO,
+            b: Vec2::ZERO,
+            radius: 0.0,
+        };
+        let square = Collider::Aabb {
+            half_extents: Vec2::splat(10.0),
+        };
+        for point in [dot, single, point_capsule] {
+            assert!(
+                point.intersects(Vec2::ZERO, &point, Vec2::ZERO),
+                "{:?}",
+                point
+            );
+            assert!(
+                point.intersects(Vec2::ZERO, &square, Vec2::ZERO),
+                "{:?}",
+                point
+            );
+            assert!(
+                square.intersects(Vec2::ZERO, &point, Vec2::ZERO),
+                "{:?}",
+                point
+            );
+            assert!(
+                !point.intersects(vec2(30.0, 0.0), &square, Vec2::ZERO),
+                "{:?}",
+                point
+            );
+            let bounds = point.bounds(Vec2::ZERO);
+            assert_eq!((bounds.w, bounds.h), (0.0, 0.0));
+        }
+        assert!(dot.intersects(vec2(1.0, 1.0), &single, Vec2::ZERO));
+        assert!(!dot.intersects(Vec2::ZERO, &single, Vec2::ZERO));
+        assert!(Collider::Circle { radius: 2.0 }.intersects(Vec2::ZERO, &single, Vec2::ZERO));
+    }
+}
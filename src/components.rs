@@ -0,0 +1,72 @@
+//! Components shared by the gameplay systems.
+
+use crate::collider::Collider;
+use crate::enemy::EnemyKind;
+use macroquad::prelude::*;
+
+pub use crate::hits::Projectile;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Transform {
+    pub pos: Vec2,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Velocity {
+    pub vel: Vec2,
+}
+
+/// Size the entity is drawn at and the shape it collides with.
+#[derive(Clone, Copy, Debug)]
+pub struct Body {
+    pub size: f32,
+    pub collider: Collider,
+}
+
+impl Body {
+    pub fn bounds(&self, pos: Vec2) -> Rect {
+        self.collider.bounds(pos)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Health {
+    pub hp: u32,
+    pub max_hp: u32,
+    /// Seconds left during which damage is ignored.
+    pub invulnerable: f32,
+}
+
+impl Health {
+    pub fn new(hp: u32) -> Self {
+        Health {
+            hp,
+            max_hp: hp,
+            invulnerable: 0.0,
+        }
+    }
+
+    pub fn dead(&self) -> bool {
+        self.hp == 0
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Player {
+    /// Which local player controls the ship, from 0.
+    pub index: usize,
+    /// Points from this ship's own kills, less any versus hit penalties.
+    pub score: u32,
+    /// Kills in a row since the ship was last hit.
+    pub chain: u32,
+    pub speed: f32,
+    pub fire_cooldown: f32,
+    /// Movement input this frame, each axis from -1.0 to 1.0. The x axis
+    /// picks the ship animation.
+    pub movement: Vec2,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Enemy {
+    pub kind: EnemyKind,
+}
@@ -0,0 +1,300 @@
+//! The daily challenge.
+//!
+//! Every run started on the same UTC date gets the same seed and the same
+//! modifiers, so everyone playing that day faces the same enemies. The first
+//! run of the day is ranked on that date's own leaderboard table; later runs
+//! are practice. Daily runs step a fixed arena at a fixed rate, whatever the
+//! window's size and frame rate, so the same inputs always meet the same
+//! enemies. The date of the last ranked attempt is stored as plain text,
+//! an `attempted YYYY-MM-DD` line.
+
+use crate::difficulty::{Difficulty, Preset};
+use crate::world::SimParams;
+use macroquad::math::{vec2, Vec2};
+use macroquad::rand::RandGenerator;
+use std::fmt;
+use std::fs;
+
+pub const DAILY_PATH: &str = "daily.dat";
+
+/// The arena every daily run is played in, that of the default window.
+pub const ARENA: Vec2 = vec2(800.0, 600.0);
+/// The fixed step every daily run advances by, as at 60 FPS.
+pub const DELTA: f32 = 1.0 / 60.0;
+
+/// Modifiers picked for each day.
+const MODIFIERS_PER_DAY: usize = 2;
+
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// A calendar date in UTC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Date {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl Date {
+    pub fn today() -> Self {
+        Date::from_unix(macroquad::miniquad::date::now())
+    }
+
+    /// The date of `seconds` since the Unix epoch.
+    pub fn from_unix(seconds: f64) -> Self {
+        // Days to civil date, after Howard Hinnant's `civil_from_days`.
+        let days = (seconds / SECONDS_PER_DAY).floor() as i64 + 719468;
+        let era = days.div_euclid(146097);
+        let day_of_era = days.rem_euclid(146097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Date {
+            year: year as i32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+
+    /// Parses the `YYYY-MM-DD` form [`Date`] displays as.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        Some(Date { year, month, day })
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Modifier {
+    /// Half again as many enemies.
+    Swarm,
+    /// Enemies fly faster.
+    Rush,
+    /// The guns fire a third less often.
+    Jammed,
+    /// The Hard difficulty curve instead of Normal.
+    Hardened,
+}
+
+impl Modifier {
+    pub const ALL: [Modifier; 4] = [
+        Modifier::Swarm,
+        Modifier::Rush,
+        Modifier::Jammed,
+        Modifier::Hardened,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Modifier::Swarm => "Swarm",
+            Modifier::Rush => "Rush",
+            Modifier::Jammed => "Jammed",
+            Modifier::Hardened => "Hardened",
+        }
+    }
+}
+
+/// One day's challenge.
+#[derive(Clone, Debug)]
+pub struct DailyChallenge {
+    pub date: Date,
+    pub seed: u64,
+    pub modifiers: Vec<Modifier>,
+}
+
+impl DailyChallenge {
+    pub fn today() -> Self {
+        DailyChallenge::for_date(Date::today())
+    }
+
+    pub fn for_date(date: Date) -> Self {
+        let seed = date.year as u64 * 10000 + date.month as u64 * 100 + date.day as u64;
+        let rng = RandGenerator::new();
+        rng.srand(seed);
+        let mut pool = Modifier::ALL.to_vec();
+        let modifiers = (0..MODIFIERS_PER_DAY)
+            .map(|_| pool.swap_remove(rng.gen_range(0, pool.len())))
+            .collect();
+        DailyChallenge {
+            date,
+            seed,
+            modifiers,
+        }
+    }
+
+    pub fn has(&self, modifier: Modifier) -> bool {
+        self.modifiers.contains(&modifier)
+    }
+
+    /// Leaderboard table for this date.
+    pub fn table(&self) -> String {
+        format!("daily.{}", self.date)
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        if self.has(Modifier::Hardened) {
+            Preset::Hard.difficulty()
+        } else {
+            Preset::Normal.difficulty()
+        }
+    }
+
+    pub fn spawn_scale(&self) -> f32 {
+        if self.has(Modifier::Swarm) {
+            1.5
+        } else {
+            1.0
+        }
+    }
+
+    pub fn speed_scale(&self) -> f32 {
+        if self.has(Modifier::Rush) {
+            1.3
+        } else {
+            1.0
+        }
+    }
+
+    /// Adjusts the parameters the challenge's world is built with. The scales
+    /// are fed in per frame instead, like adaptive difficulty's.
+    pub fn apply(&self, params: &mut SimParams) {
+        if self.has(Modifier::Jammed) {
+            params.fire_cooldown *= 1.5;
+        }
+    }
+}
+
+/// When the ranked daily attempt was last used.
+#[derive(Default)]
+pub struct DailyAttempts {
+    last: Option<Date>,
+}
+
+impl DailyAttempts {
+    pub fn load(path: &str) -> Self {
+        let last = fs::read_to_string(path).ok().and_then(|contents| {
+            contents.lines().find_map(|line| {
+                let mut parts = line.split_whitespace();
+                match (parts.next(), parts.next()) {
+                    (Some("attempted"), Some(date)) => Date::parse(date),
+                    _ => None,
+                }
+            })
+        });
+        DailyAttempts { last }
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        match self.last {
+            Some(date) => fs::write(path, format!("attempted {}\n", date)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn attempted(&self, date: Date) -> bool {
+        self.last == Some(date)
+    }
+
+    pub fn record(&mut self, date: Date) {
+        self.last = Some(date);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date { year, month, day }
+    }
+
+    #[test]
+    fn the_epoch_is_the_first_of_january_1970() {
+        assert_eq!(Date::from_unix(0.0), date(1970, 1, 1));
+        assert_eq!(Date::from_unix(SECONDS_PER_DAY - 1.0), date(1970, 1, 1));
+        assert_eq!(Date::from_unix(SECONDS_PER_DAY), date(1970, 1, 2));
+        assert_eq!(Date::from_unix(-1.0), date(1969, 12, 31));
+    }
+
+    #[test]
+    fn leap_days() {
+        // 2024-02-29T00:00:00Z and 2000-02-29T12:00:00Z.
+        assert_eq!(Date::from_unix(1709164800.0), date(2024, 2, 29));
+        assert_eq!(
+            Date::from_unix(1709164800.0 + SECONDS_PER_DAY),
+            date(2024, 3, 1)
+        );
+        assert_eq!(Date::from_unix(951825600.0), date(2000, 2, 29));
+        // 1900 was not a leap year, so the day after its 28 February is 1 March.
+        let february_28_1900 = -2203977600.0;
+        assert_eq!(Date::from_unix(february_28_1900), date(1900, 2, 28));
+        assert_eq!(
+            Date::from_unix(february_28_1900 + SECONDS_PER_DAY),
+            date(1900, 3, 1)
+        );
+    }
+
+    #[test]
+    fn parses_what_it_displays() {
+        let leap = date(2024, 2, 29);
+        assert_eq!(leap.to_string(), "2024-02-29");
+        assert_eq!(Date::parse("2024-02-29"), Some(leap));
+        assert_eq!(Date::parse("0987-01-05"), Some(date(987, 1, 5)));
+        assert_eq!(Date::parse("2024-02"), None);
+        assert_eq!(Date::parse("2024-02-29x"), None);
+        assert_eq!(Date::parse("yesterday"), None);
+    }
+
+    #[test]
+    fn a_date_always_gets_the_same_challenge() {
+        let first = DailyChallenge::for_date(date(2024, 2, 29));
+        let again = DailyChallenge::for_date(date(2024, 2, 29));
+        assert_eq!(first.seed, 20240229);
+        assert_eq!(again.seed, first.seed);
+        assert_eq!(again.modifiers, first.modifiers);
+        assert_eq!(first.table(), "daily.2024-02-29");
+    }
+
+    #[test]
+    fn each_day_gets_distinct_modifiers() {
+        for day in 1..=28 {
+            let daily = DailyChallenge::for_date(date(2025, 2, day));
+            assert_eq!(daily.modifiers.len(), MODIFIERS_PER_DAY);
+            assert_ne!(daily.modifiers[0], daily.modifiers[1]);
+        }
+    }
+
+    #[test]
+    fn every_modifier_changes_the_run() {
+        for modifier in Modifier::ALL {
+            let daily = DailyChallenge {
+                date: date(2024, 1, 1),
+                seed: 0,
+                modifiers: vec![modifier],
+            };
+            let mut params = SimParams::default();
+            daily.apply(&mut params);
+            let changed = params.fire_cooldown != SimParams::default().fire_cooldown
+                || daily.spawn_scale() != 1.0
+                || daily.speed_scale() != 1.0
+                || daily.difficulty().preset != Preset::Normal;
+            assert!(changed, "{} changes nothing", modifier.name());
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Difficulty presets and the curve that ramps them up over a run.
+//!
+//! A run's progress goes from 0.0 to 1.0 as either the elapsed time reaches
+//! `ramp_seconds` or the score reaches `ramp_score`, whichever comes first.
+//! Every tunable is a [`Ramp`] interpolated by that progress.
+
+use crate::enemy::EnemyKind;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Preset {
+    Easy,
+    Normal,
+    Hard,
+    Insane,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 4] = [Preset::Easy, Preset::Normal, Preset::Hard, Preset::Insane];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Easy => "Easy",
+            Preset::Normal => "Normal",
+            Preset::Hard => "Hard",
+            Preset::Insane => "Insane",
+        }
+    }
+
+    /// Stable identifier used for leaderboard tables.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Preset::Easy => "easy",
+            Preset::Normal => "normal",
+            Preset::Hard => "hard",
+            Preset::Insane => "insane",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Preset> {
+        Preset::ALL.into_iter().find(|preset| preset.key() == key)
+    }
+
+    pub fn next(&self) -> Preset {
+        match self {
+            Preset::Easy => Preset::Normal,
+            Preset::Normal => Preset::Hard,
+            Preset::Hard => Preset::Insane,
+            Preset::Insane => Preset::Easy,
+        }
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        match self {
+            Preset::Easy => Difficulty {
+                preset: *self,
+                ramp_seconds: 240.0,
+                ramp_score: 15000.0,
+                spawn_rate: Ramp::new(2.0, 4.0),
+                speed_min: Ramp::new(40.0, 60.0),
+                speed_max: Ramp::new(110.0, 180.0),
+                weights: [
+                    Ramp::new(1.5, 1.2),
+                    Ramp::new(1.0, 1.0),
+                    Ramp::new(0.6, 1.0),
+                ],
+            },
+            Preset::Normal => Difficulty {
+                preset: *self,
+                ramp_seconds: 180.0,
+                ramp_score: 10000.0,
+                spawn_rate: Ramp::new(3.0, 6.0),
+                speed_min: Ramp::new(50.0, 90.0),
+                speed_max: Ramp::new(150.0, 260.0),
+                weights: [
+                    Ramp::new(1.0, 1.0),
+                    Ramp::new(1.0, 1.2),
+                    Ramp::new(1.0, 1.5),
+                ],
+            },
+            Preset::Hard => Difficulty {
+                preset: *self,
+                ramp_seconds: 150.0,
+                ramp_score: 8000.0,
+                spawn_rate: Ramp::new(4.0, 8.0),
+                speed_min: Ramp::new(60.0, 110.0),
+                speed_max: Ramp::new(180.0, 300.0),
+                weights: [
+                    Ramp::new(1.0, 0.8),
+                    Ramp::new(1.0, 1.2),
+                    Ramp::new(1.0, 2.0),
+                ],
+            },
+            Preset::Insane => Difficulty {
+                preset: *self,
+                ramp_seconds: 120.0,
+                ramp_score: 6000.0,
+                spawn_rate: Ramp::new(6.0, 12.0),
+                speed_min: Ramp::new(90.0, 150.0),
+                speed_max: Ramp::new(220.0, 360.0),
+                weights: [
+                    Ramp::new(0.8, 0.6),
+                    Ramp::new(1.0, 1.2),
+                    Ramp::new(1.5, 2.5),
+                ],
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Ramp {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Ramp {
+    pub const fn new(start: f32, end: f32) -> Self {
+        Ramp { start, end }
+    }
+
+    pub fn at(&self, progress: f32) -> f32 {
+        self.start + (self.end - self.start) * progress.clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Difficulty {
+    pub preset: Preset,
+    pub ramp_seconds: f32,
+    pub ramp_score: f32,
+    /// Enemies spawned per second.
+    pub spawn_rate: Ramp,
+    pub speed_min: Ramp,
+    pub speed_max: Ramp,
+    /// Relative spawn weights, indexed like [`EnemyKind::ALL`].
+    pub weights: [Ramp; 3],
+}
+
+impl Difficulty {
+    pub fn progress(&self, elapsed: f32, score: u32) -> f32 {
+        (elapsed / self.ramp_seconds)
+            .max(score as f32 / self.ramp_score)
+            .clamp(0.0, 1.0)
+    }
+
+    /// Probability of spawning an enemy during a frame lasting `delta` seconds.
+    pub fn spawn_chance(&self, progress: f32, delta: f32) -> f32 {
+        (self.spawn_rate.at(progress) * delta).min(1.0)
+    }
+
+    pub fn speed_range(&self, progress: f32) -> (f32, f32) {
+        (self.speed_min.at(progress), self.speed_max.at(progress))
+    }
+
+    /// Picks an enemy kind from the weighted mix, `roll` being in `0.0..1.0`.
+    pub fn pick_kind(&self, progress: f32, roll: f32) -> EnemyKind {
+        let weights = self.weights.map(|weight| weight.at(progress).max(0.0));
+        let mut target = roll * weights.iter().sum::<f32>();
+        for (kind, weight) in EnemyKind::ALL.iter().zip(weights) {
+            if target < weight {
+                return *kind;
+            }
+            target -= weight;
+        }
+        EnemyKind::Big
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_round_trip_through_their_keys_and_cycle() {
+        for preset in Preset::ALL {
+            assert_eq!(Preset::from_key(preset.key()), Some(preset));
+            assert_eq!(preset.difficulty().preset, preset);
+        }
+        assert_eq!(Preset::from_key("nightmare"), None);
+        let mut preset = Preset::Easy;
+        for expected in [Preset::Normal, Preset::Hard, Preset::Insane, Preset::Easy] {
+            preset = preset.next();
+            assert_eq!(preset, expected);
+        }
+    }
+
+    #[test]
+    fn each_preset_is_harder_than_the_one_before() {
+        for pair in Preset::ALL.windows(2) {
+            let (easier, harder) = (pair[0].difficulty(), pair[1].difficulty());
+            assert!(harder.ramp_seconds < easier.ramp_seconds, "{:?}", pair);
+            assert!(harder.ramp_score < easier.ramp_score, "{:?}", pair);
+            for progress in [0.0, 0.5, 1.0] {
+                assert!(
+                    harder.spawn_rate.at(progress) > easier.spawn_rate.at(progress),
+                    "{:?} at {}",
+                    pair,
+                    progress
+                );
+                let (easier_min, easier_max) = easier.speed_range(progress);
+                let (harder_min, harder_max) = harder.speed_range(progress);
+                assert!(harder_min > easier_min, "{:?} at {}", pair, progress);
+                assert!(harder_max > easier_max, "{:?} at {}", pair, progress);
+            }
+        }
+    }
+
+    #[test]
+    fn every_preset_ramps_up_with_sane_speeds() {
+        for preset in Preset::ALL {
+            let difficulty = preset.difficulty();
+            assert!(difficulty.spawn_rate.end > difficulty.spawn_rate.start);
+            for progress in [0.0, 1.0] {
+                let (min, max) = difficulty.speed_range(progress);
+                assert!(0.0 < min && min < max, "{:?}", preset);
+            }
+        }
+    }
+
+    #[test]
+    fn progress_follows_time_or_score_whichever_is_further() {
+        let difficulty = Preset::Normal.difficulty();
+        assert_eq!(difficulty.progress(0.0, 0), 0.0);
+        assert_eq!(difficulty.progress(90.0, 0), 0.5);
+        assert_eq!(difficulty.progress(0.0, 5000), 0.5);
+        assert_eq!(difficulty.progress(45.0, 5000), 0.5);
+        assert_eq!(difficulty.progress(900.0, 100_000), 1.0);
+    }
+
+    #[test]
+    fn ramps_clamp_outside_the_run() {
+        let ramp = Ramp::new(2.0, 4.0);
+        assert_eq!(ramp.at(-1.0), 2.0);
+        assert_eq!(ramp.at(0.5), 3.0);
+        assert_eq!(ramp.at(2.0), 4.0);
+    }
+
+    #[test]
+    fn spawn_chance_is_a_probability() {
+        let difficulty = Preset::Insane.difficulty();
+        assert_eq!(difficulty.spawn_chance(0.0, 0.1), 0.6);
+        assert_eq!(difficulty.spawn_chance(1.0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn picks_kinds_by_weight() {
+        // Normal starts with an even mix.
+        let difficulty = Preset::Normal.difficulty();
+        assert_eq!(difficulty.pick_kind(0.0, 0.0), EnemyKind::Small);
+        assert_eq!(difficulty.pick_kind(0.0, 0.5), EnemyKind::Medium);
+        assert_eq!(difficulty.pick_kind(0.0, 0.99), EnemyKind::Big);
+        // By the end big enemies are 1.5 of 3.7.
+        assert_eq!(difficulty.pick_kind(1.0, 0.6), EnemyKind::Big);
+    }
+}
@@ -0,0 +1,100 @@
+//! Minimal entity/component storage.
+//!
+//! Entities are generational handles handed out by a [`Pool`], so a despawned
+//! entity's handle never aliases whatever reuses its slot. Each component type
+//! lives in its own sparse [`Components`] store indexed by entity slot; an
+//! entity is just the set of stores that hold a value for it.
+
+use crate::pool::{Handle, Pool};
+
+pub type Entity = Handle;
+
+pub type Entities = Pool<()>;
+
+pub struct Components<T> {
+    slots: Vec<Option<(u32, T)>>,
+    len: usize,
+}
+
+impl<T> Default for Components<T> {
+    fn default() -> Self {
+        Components {
+            slots: vec![],
+            len: 0,
+        }
+    }
+}
+
+impl<T> Components<T> {
+    pub fn new() -> Self {
+        Components::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, entity: Entity, value: T) {
+        let index = entity.index();
+        if index >= self.slots.len() {
+            self.slots.resize_with(index + 1, || None);
+        }
+        if self.slots[index].is_none() {
+            self.len += 1;
+        }
+        self.slots[index] = Some((entity.generation(), value));
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        let slot = self.slots.get_mut(entity.index())?;
+        if !matches!(slot, Some((generation, _)) if *generation == entity.generation()) {
+            return None;
+        }
+        self.len -= 1;
+        slot.take().map(|(_, value)| value)
+    }
+
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.get(entity).is_some()
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        match self.slots.get(entity.index()) {
+            Some(Some((generation, value))) if *generation == entity.generation() => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        match self.slots.get_mut(entity.index()) {
+            Some(Some((generation, value))) if *generation == entity.generation() => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.slots.clear();
+        self.len = 0;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let (generation, value) = slot.as_ref()?;
+            Some((Entity::new(index as u32, *generation), value))
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let (generation, value) = slot.as_mut()?;
+                Some((Entity::new(index as u32, *generation), value))
+            })
+    }
+}
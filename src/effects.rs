@@ -0,0 +1,83 @@
+//! Sound and particle reactions to gameplay events.
+//!
+//! Both subscribers only decide what to play; the sounds and the particle
+//! emitters themselves need an audio device and a GPU, so what plays a sound
+//! and what an explosion is drawn with are handed in by the gameplay scene.
+
+use crate::events::{Event, Subscriber};
+use crate::pool::Pool;
+use macroquad::prelude::*;
+use macroquad_particles::{Emitter, EmitterConfig};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sfx {
+    Laser,
+    Explosion,
+}
+
+/// Plays the sound effects for gameplay events through `play`.
+pub struct SoundEffects<F: FnMut(Sfx)> {
+    pub play: F,
+}
+
+impl<F: FnMut(Sfx)> Subscriber for SoundEffects<F> {
+    fn notify(&mut self, event: &Event) {
+        match event {
+            Event::ShotFired { .. } => (self.play)(Sfx::Laser),
+            Event::EnemyKilled { .. } => (self.play)(Sfx::Explosion),
+            _ => {}
+        }
+    }
+}
+
+/// A one-shot burst of particles.
+pub trait Burst: Sized {
+    type Config;
+
+    /// A new burst of `amount` particles.
+    fn burst(config: &Self::Config, amount: u32) -> Self;
+
+    /// Bursts again with `amount` particles, reusing what it was drawn with.
+    fn reburst(&mut self, amount: u32);
+}
+
+impl Burst for Emitter {
+    type Config = EmitterConfig;
+
+    fn burst(config: &EmitterConfig, amount: u32) -> Emitter {
+        Emitter::new(EmitterConfig {
+            amount,
+            ..config.clone()
+        })
+    }
+
+    fn reburst(&mut self, amount: u32) {
+        self.config.amount = amount;
+        self.config.emitting = true;
+    }
+}
+
+/// Explosion particles for killed enemies. Bursts are recycled, the oldest
+/// one first when the pool is full.
+pub struct Explosions<B: Burst> {
+    pub pool: Pool<(B, Vec2)>,
+    pub config: B::Config,
+    /// Particles per pixel of the enemy's size.
+    pub particles: f32,
+}
+
+impl<B: Burst> Subscriber for Explosions<B> {
+    fn notify(&mut self, event: &Event) {
+        let Event::EnemyKilled { pos, size, .. } = *event else {
+            return;
+        };
+        let amount = (size.round() * self.particles) as u32;
+        self.pool.insert_recycling_with(|old| match old {
+            Some((mut explosion, _)) => {
+                explosion.reburst(amount);
+                (explosion, pos)
+            }
+            None => (B::burst(&self.config, amount), pos),
+        });
+    }
+}
@@ -0,0 +1,46 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EnemyKind {
+    Small,
+    Medium,
+    Big,
+}
+
+impl EnemyKind {
+    pub const ALL: [EnemyKind; 3] = [EnemyKind::Small, EnemyKind::Medium, EnemyKind::Big];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnemyKind::Small => "Small",
+            EnemyKind::Medium => "Medium",
+            EnemyKind::Big => "Big",
+        }
+    }
+
+    /// Identifier used in save files.
+    pub fn key(&self) -> &'static str {
+        match self {
+            EnemyKind::Small => "small",
+            EnemyKind::Medium => "medium",
+            EnemyKind::Big => "big",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<EnemyKind> {
+        EnemyKind::ALL.into_iter().find(|kind| kind.key() == key)
+    }
+
+    /// Every kind goes down to one hit, as it always has.
+    pub fn max_hp(&self) -> u32 {
+        match self {
+            EnemyKind::Small | EnemyKind::Medium | EnemyKind::Big => 1,
+        }
+    }
+
+    pub fn size_range(&self) -> (f32, f32) {
+        match self {
+            EnemyKind::Small => (16.0, 32.0),
+            EnemyKind::Medium => (32.0, 48.0),
+            EnemyKind::Big => (48.0, 64.0),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Gameplay events.
+//!
+//! The simulation never plays sounds, spawns particles or counts statistics
+//! itself. Systems publish [`Event`]s to the world's [`EventQueue`] instead,
+//! and each reaction (audio, effects, statistics, ...) is a [`Subscriber`]
+//! that the queue is dispatched to once the step is over.
+
+use crate::enemy::EnemyKind;
+use macroquad::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    /// The ship's movement input changed to something other than standing
+    /// still.
+    ShipMoved {
+        movement: Vec2,
+    },
+    ShotFired {
+        pos: Vec2,
+    },
+    /// A projectile left the arena or ran out of pierce.
+    ShotExpired {
+        /// Whether it hit anything during its life.
+        hit: bool,
+    },
+    EnemyKilled {
+        kind: EnemyKind,
+        pos: Vec2,
+        size: f32,
+        points: u32,
+        /// Combo chain length including this kill.
+        chain: u32,
+    },
+    /// An enemy passed the ship within a few pixels without hitting it.
+    NearMiss {
+        kind: EnemyKind,
+    },
+    PlayerHit {
+        /// Index of the ship's player.
+        player: usize,
+        pos: Vec2,
+        /// Hit points left.
+        hp: u32,
+        /// The kind of enemy the ship ran into, or `None` for the other
+        /// player's shot.
+        by: Option<EnemyKind>,
+    },
+    PlayerDied {
+        player: usize,
+        pos: Vec2,
+        by: Option<EnemyKind>,
+    },
+    /// The game mode or the versus round timer ended the run with a ship
+    /// still alive.
+    RunFinished,
+    /// The run is over, however it ended. Published last in its step.
+    RunEnded {
+        /// The final score, including points that weren't for a kill.
+        score: u32,
+    },
+}
+
+pub trait Subscriber {
+    fn notify(&mut self, event: &Event);
+}
+
+/// Events published during the current step, in the order they happened.
+#[derive(Default)]
+pub struct EventQueue {
+    events: Vec<Event>,
+}
+
+impl EventQueue {
+    pub fn new() -> Self {
+        EventQueue::default()
+    }
+
+    pub fn publish(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Hands every queued event, in order, to `subscriber`.
+    pub fn dispatch(&self, subscriber: &mut impl Subscriber) {
+        for event in &self.events {
+            subscriber.notify(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::achievements::Achievements;
+    use crate::effects::{Burst, Explosions, Sfx, SoundEffects};
+    use crate::pool::Pool;
+    use crate::stats::RunStats;
+
+    fn killed(kind: EnemyKind, size: f32, points: u32, chain: u32) -> Event {
+        Event::EnemyKilled {
+            kind,
+            pos: vec2(size, 0.0),
+            size,
+            points,
+            chain,
+        }
+    }
+
+    /// A run's worth of events: two shots, one of which kills, and a near
+    /// miss before the ship goes down.
+    fn run() -> EventQueue {
+        let mut queue = EventQueue::new();
+        for event in [
+            Event::ShipMoved {
+                movement: vec2(1.0, 0.0),
+            },
+            Event::ShotFired { pos: Vec2::ZERO },
+            Event::ShotFired { pos: Vec2::ZERO },
+            killed(EnemyKind::Medium, 32.0, 20, 1),
+            Event::ShotExpired { hit: true },
+            Event::ShotExpired { hit: false },
+            Event::NearMiss {
+                kind: EnemyKind::Big,
+            },
+            Event::PlayerHit {
+                player: 0,
+                pos: Vec2::ZERO,
+                hp: 0,
+                by: Some(EnemyKind::Big),
+            },
+            Event::PlayerDied {
+                player: 0,
+                pos: Vec2::ZERO,
+                by: Some(EnemyKind::Big),
+            },
+            Event::RunEnded { score: 20 },
+        ] {
+            queue.publish(event);
+        }
+        queue
+    }
+
+    #[test]
+    fn sound_effects_play_for_shots_and_kills() {
+        let mut played = Vec::new();
+        run().dispatch(&mut SoundEffects {
+            play: |sfx| played.push(sfx),
+        });
+        assert_eq!(played, [Sfx::Laser, Sfx::Laser, Sfx::Explosion]);
+    }
+
+    /// Counts its particles instead of drawing them.
+    impl Burst for u32 {
+        type Config = ();
+
+        fn burst(_config: &(), amount: u32) -> u32 {
+            amount
+        }
+
+        fn reburst(&mut self, amount: u32) {
+            *self = amount;
+        }
+    }
+
+    #[test]
+    fn explosions_burst_where_enemies_die_and_recycle_the_oldest() {
+        let mut explosions = Explosions::<u32> {
+            pool: Pool::new(2),
+            config: (),
+            particles: 0.5,
+        };
+        run().dispatch(&mut explosions);
+        let bursts: Vec<_> = explosions.pool.values().copied().collect();
+        assert_eq!(bursts, [(16, vec2(32.0, 0.0))]);
+
+        explosions.notify(&killed(EnemyKind::Small, 17.0, 10, 2));
+        explosions.notify(&killed(EnemyKind::Big, 64.0, 40, 3));
+        let mut bursts: Vec<_> = explosions.pool.values().copied().collect();
+        bursts.sort_by_key(|&(amount, _)| amount);
+        assert_eq!(bursts, [(8, vec2(17.0, 0.0)), (32, vec2(64.0, 0.0))]);
+    }
+
+    #[test]
+    fn run_stats_count_shots_hits_kills_and_near_misses() {
+        let mut stats = RunStats::default();
+        run().dispatch(&mut stats);
+        assert_eq!(stats.shots_fired, 2);
+        assert_eq!(stats.shots_hit, 1);
+        assert_eq!(stats.accuracy(), 0.5);
+        assert_eq!(stats.kills, 1);
+        assert_eq!(stats.kills_of(EnemyKind::Medium), 1);
+        assert_eq!(stats.kills_of(EnemyKind::Big), 0);
+        assert_eq!(stats.near_misses, 1);
+    }
+
+    #[test]
+    fn achievements_count_kills_only_during_a_run() {
+        let mut achievements = Achievements::default();
+        run().dispatch(&mut achievements);
+        assert!(achievements.take_unlocked().is_empty());
+
+        achievements.start_run(0);
+        run().dispatch(&mut achievements);
+        let ids: Vec<_> = achievements
+            .take_unlocked()
+            .iter()
+            .map(|achievement| achievement.id)
+            .collect();
+        assert_eq!(ids, ["first_blood"]);
+    }
+}
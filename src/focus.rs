@@ -0,0 +1,50 @@
+//! Window focus tracking.
+//!
+//! miniquad reports focus changes as minimize/restore events: on desktop when
+//! the window loses focus, on the web when the canvas loses focus or the tab
+//! is hidden. macroquad doesn't expose them directly, so this replays its raw
+//! input queue through an event handler of our own.
+
+use macroquad::input::utils::{register_input_subscriber, repeat_all_miniquad_input};
+use macroquad::miniquad::EventHandler;
+
+#[derive(Default)]
+struct FocusEvents {
+    lost: bool,
+}
+
+impl EventHandler for FocusEvents {
+    fn update(&mut self) {}
+
+    fn draw(&mut self) {}
+
+    fn window_minimized_event(&mut self) {
+        self.lost = true;
+    }
+}
+
+pub struct FocusWatcher {
+    subscriber: usize,
+}
+
+impl FocusWatcher {
+    pub fn new() -> Self {
+        FocusWatcher {
+            subscriber: register_input_subscriber(),
+        }
+    }
+
+    /// Whether the window lost focus since the last call. Must be called
+    /// every frame, or queued input piles up.
+    pub fn lost_focus(&mut self) -> bool {
+        let mut events = FocusEvents::default();
+        repeat_all_miniquad_input(&mut events, self.subscriber);
+        events.lost
+    }
+}
+
+impl Default for FocusWatcher {
+    fn default() -> Self {
+        FocusWatcher::new()
+    }
+}
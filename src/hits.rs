@@ -0,0 +1,295 @@
+//! Projectile hit resolution.
+//!
+//! The rules, applied once per frame after the narrowphase has found every
+//! overlapping projectile/target pair:
+//!
+//! - Projectiles are resolved oldest first. Each one walks its
+//!   overlapping targets nearest first, ties broken by target age.
+//! - A projectile damages a given target at most once over its whole life, so
+//!   piercing shots don't grind through an enemy they stay inside of.
+//! - Targets already destroyed earlier in the frame are skipped, so a kill is
+//!   only ever credited once.
+//! - Every hit uses up one point of pierce; a projectile with none left is
+//!   spent and stops hitting anything.
+//!
+//! Hits are returned in the order they were applied, which makes the result
+//! deterministic for a given set of overlaps.
+
+use crate::components::Health;
+use crate::ecs::{Components, Entities, Entity};
+
+pub struct Projectile {
+    /// The ship that fired it.
+    pub owner: Entity,
+    pub damage: u32,
+    /// Targets this projectile can still hit.
+    pub pierce: u32,
+    hit: Vec<Entity>,
+}
+
+impl Projectile {
+    pub fn new(owner: Entity, damage: u32, pierce: u32) -> Self {
+        Projectile {
+            owner,
+            damage,
+            pierce,
+            hit: vec![],
+        }
+    }
+
+    pub fn spent(&self) -> bool {
+        self.pierce == 0
+    }
+
+    /// Uses up all remaining pierce without hitting anything.
+    pub fn spend(&mut self) {
+        self.pierce = 0;
+    }
+
+    pub fn has_hit(&self) -> bool {
+        !self.hit.is_empty()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Overlap {
+    pub projectile: Entity,
+    pub target: Entity,
+    /// Distance between the two, used to hit the nearest target first.
+    pub distance: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hit {
+    pub projectile: Entity,
+    pub target: Entity,
+    pub damage: u32,
+    pub killed: bool,
+}
+
+/// Applies `overlaps` to the projectiles and the targets' health and appends
+/// every hit to `hits`. `overlaps` is sorted in place.
+pub fn resolve(
+    overlaps: &mut [Overlap],
+    entities: &Entities,
+    projectiles: &mut Components<Projectile>,
+    healths: &mut Components<Health>,
+    hits: &mut Vec<Hit>,
+) {
+    overlaps.sort_by(|a, b| {
+        entities
+            .spawn_order(a.projectile)
+            .cmp(&entities.spawn_order(b.projectile))
+            .then(a.distance.total_cmp(&b.distance))
+            .then(
+                entities
+                    .spawn_order(a.target)
+                    .cmp(&entities.spawn_order(b.target)),
+            )
+    });
+
+    for overlap in overlaps.iter() {
+        let (Some(projectile), Some(target)) = (
+            projectiles.get_mut(overlap.projectile),
+            healths.get_mut(overlap.target),
+        ) else {
+            continue;
+        };
+        if projectile.spent() || target.dead() || projectile.hit.contains(&overlap.target) {
+            continue;
+        }
+
+        projectile.hit.push(overlap.target);
+        projectile.pierce -= 1;
+        target.hp = target.hp.saturating_sub(projectile.damage);
+        hits.push(Hit {
+            projectile: overlap.projectile,
+            target: overlap.target,
+            damage: projectile.damage,
+            killed: target.dead(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pool::Pool;
+
+    struct Fixture {
+        entities: Entities,
+        projectiles: Components<Projectile>,
+        healths: Components<Health>,
+        owner: Entity,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let mut entities = Pool::new(64);
+            let owner = entities.insert(()).unwrap();
+            Fixture {
+                entities,
+                projectiles: Components::new(),
+                healths: Components::new(),
+                owner,
+            }
+        }
+
+        fn bullet(&mut self, damage: u32, pierce: u32) -> Entity {
+            let bullet = self.entities.insert(()).unwrap();
+            self.projectiles
+                .insert(bullet, Projectile::new(self.owner, damage, pierce));
+            bullet
+        }
+
+        fn enemy(&mut self, hp: u32) -> Entity {
+            let enemy = self.entities.insert(()).unwrap();
+            self.healths.insert(enemy, Health::new(hp));
+            enemy
+        }
+
+        fn resolve(&mut self, overlaps: &mut [Overlap]) -> Vec<Hit> {
+            let mut hits = vec![];
+            resolve(
+                overlaps,
+                &self.entities,
+                &mut self.projectiles,
+                &mut self.healths,
+                &mut hits,
+            );
+            hits
+        }
+
+        fn hp(&self, enemy: Entity) -> u32 {
+            self.healths.get(enemy).unwrap().hp
+        }
+
+        fn pierce(&self, bullet: Entity) -> u32 {
+            self.projectiles.get(bullet).unwrap().pierce
+        }
+    }
+
+    fn overlap(projectile: Entity, target: Entity, distance: f32) -> Overlap {
+        Overlap {
+            projectile,
+            target,
+            distance,
+        }
+    }
+
+    #[test]
+    fn one_bullet_over_two_enemies_hits_the_nearest() {
+        let mut fixture = Fixture::new();
+        let near = fixture.enemy(1);
+        let far = fixture.enemy(1);
+        let bullet = fixture.bullet(1, 1);
+        let hits = fixture.resolve(&mut [overlap(bullet, far, 9.0), overlap(bullet, near, 3.0)]);
+        assert_eq!(
+            hits,
+            vec![Hit {
+                projectile: bullet,
+                target: near,
+                damage: 1,
+                killed: true,
+            }]
+        );
+        assert_eq!(fixture.hp(far), 1);
+        assert!(fixture.projectiles.get(bullet).unwrap().spent());
+    }
+
+    #[test]
+    fn pierce_limits_how_many_enemies_a_bullet_hits() {
+        let mut fixture = Fixture::new();
+        let enemies = [fixture.enemy(1), fixture.enemy(1), fixture.enemy(1)];
+        let bullet = fixture.bullet(1, 2);
+        let mut overlaps: Vec<Overlap> = enemies
+            .iter()
+            .enumerate()
+            .map(|(index, &enemy)| overlap(bullet, enemy, index as f32))
+            .collect();
+        let hits = fixture.resolve(&mut overlaps);
+        let targets: Vec<Entity> = hits.iter().map(|hit| hit.target).collect();
+        assert_eq!(targets, vec![enemies[0], enemies[1]]);
+        assert_eq!(fixture.hp(enemies[2]), 1);
+        assert_eq!(fixture.pierce(bullet), 0);
+    }
+
+    #[test]
+    fn two_bullets_on_one_enemy_kill_it_once() {
+        let mut fixture = Fixture::new();
+        let enemy = fixture.enemy(1);
+        let older = fixture.bullet(1, 1);
+        let newer = fixture.bullet(1, 1);
+        let hits = fixture.resolve(&mut [overlap(newer, enemy, 0.0), overlap(older, enemy, 5.0)]);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].projectile, older);
+        assert!(hits[0].killed);
+        // The newer bullet flies on, pierce intact.
+        assert_eq!(fixture.pierce(newer), 1);
+    }
+
+    #[test]
+    fn two_bullets_wear_down_a_tough_enemy() {
+        let mut fixture = Fixture::new();
+        let enemy = fixture.enemy(2);
+        let older = fixture.bullet(1, 1);
+        let newer = fixture.bullet(1, 1);
+        let hits = fixture.resolve(&mut [overlap(newer, enemy, 0.0), overlap(older, enemy, 0.0)]);
+        let killed: Vec<(Entity, bool)> = hits
+            .iter()
+            .map(|hit| (hit.projectile, hit.killed))
+            .collect();
+        assert_eq!(killed, vec![(older, false), (newer, true)]);
+        assert_eq!(fixture.hp(enemy), 0);
+    }
+
+    #[test]
+    fn a_piercing_bullet_hits_an_enemy_only_once() {
+        let mut fixture = Fixture::new();
+        let enemy = fixture.enemy(3);
+        let bullet = fixture.bullet(1, 3);
+        assert_eq!(fixture.resolve(&mut [overlap(bullet, enemy, 0.0)]).len(), 1);
+        // Still inside the enemy next frame.
+        assert!(fixture
+            .resolve(&mut [overlap(bullet, enemy, 0.0)])
+            .is_empty());
+        assert_eq!(fixture.hp(enemy), 2);
+        assert_eq!(fixture.pierce(bullet), 2);
+    }
+
+    #[test]
+    fn resolution_order_does_not_depend_on_overlap_order() {
+        let mut results = vec![];
+        for rotation in 0..6 {
+            let mut fixture = Fixture::new();
+            let enemies = [fixture.enemy(1), fixture.enemy(2), fixture.enemy(1)];
+            let bullets = [
+                fixture.bullet(1, 2),
+                fixture.bullet(2, 1),
+                fixture.bullet(1, 1),
+            ];
+            let mut overlaps = vec![];
+            for (b, &bullet) in bullets.iter().enumerate() {
+                for (e, &enemy) in enemies.iter().enumerate() {
+                    // Equal distances for the first two enemies, so ties are
+                    // broken by enemy age.
+                    let distance = if e < 2 { 1.0 } else { 2.0 + b as f32 };
+                    overlaps.push(overlap(bullet, enemy, distance));
+                }
+            }
+            overlaps.rotate_left(rotation);
+            if rotation % 2 == 1 {
+                overlaps.reverse();
+            }
+            results.push(fixture.resolve(&mut overlaps));
+        }
+        assert!(results.windows(2).all(|pair| pair[0] == pair[1]));
+        // Oldest bullet first, each taking its nearest live enemies, oldest
+        // first among equals.
+        let order: Vec<(usize, usize)> = results[0]
+            .iter()
+            .map(|hit| (hit.projectile.index(), hit.target.index()))
+            .collect();
+        assert_eq!(order, vec![(4, 1), (4, 2), (5, 2), (6, 3)]);
+    }
+}
@@ -0,0 +1,156 @@
+//! Local leaderboards, in tables named after the preset, game mode or daily
+//! challenge a run was played on.
+//!
+//! Stored as plain text, one `table score mutators` line per entry, so the
+//! file stays easy to inspect and edit by hand like `highscore.dat`.
+//! Lines without the mutators column, from before mutators existed, load as
+//! runs without any.
+
+use crate::mutators::Mutators;
+use std::collections::BTreeMap;
+use std::fs;
+
+pub const MAX_ENTRIES: usize = 10;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Entry {
+    pub score: u32,
+    /// Mutators the run was played with.
+    pub mutators: Mutators,
+}
+
+#[derive(Default)]
+pub struct Leaderboard {
+    tables: BTreeMap<String, Vec<Entry>>,
+}
+
+impl Leaderboard {
+    pub fn load(path: &str) -> Self {
+        let mut leaderboard = Leaderboard::default();
+        if let Ok(contents) = fs::read_to_string(path) {
+            for line in contents.lines() {
+                let mut parts = line.split_whitespace();
+                if let (Some(table), Some(Ok(score))) =
+                    (parts.next(), parts.next().map(str::parse::<u32>))
+                {
+                    let mutators = parts.next().map(Mutators::from_key).unwrap_or_default();
+                    leaderboard.submit(table, Entry { score, mutators });
+                }
+            }
+        }
+        leaderboard
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let mut contents = String::new();
+        for (table, entries) in &self.tables {
+            for entry in entries {
+                contents.push_str(&format!(
+                    "{} {} {}\n",
+                    table,
+                    entry.score,
+                    entry.mutators.key()
+                ));
+            }
+        }
+        fs::write(path, contents)
+    }
+
+    pub fn entries(&self, table: &str) -> &[Entry] {
+        self.tables
+            .get(table)
+            .map_or(&[], |entries| entries.as_slice())
+    }
+
+    pub fn best(&self, table: &str) -> u32 {
+        self.entries(table).first().map_or(0, |entry| entry.score)
+    }
+
+    /// Records `entry` and returns its zero-based rank if it made the table.
+    pub fn submit(&mut self, table: &str, entry: Entry) -> Option<usize> {
+        let entries = self.tables.entry(table.to_string()).or_default();
+        let position = entries.partition_point(|other| other.score >= entry.score);
+        if position >= MAX_ENTRIES {
+            return None;
+        }
+        entries.insert(position, entry);
+        entries.truncate(MAX_ENTRIES);
+        Some(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scores(leaderboard: &Leaderboard, table: &str) -> Vec<u32> {
+        leaderboard
+            .entries(table)
+            .iter()
+            .map(|entry| entry.score)
+            .collect()
+    }
+
+    fn submit(leaderboard: &mut Leaderboard, table: &str, score: u32) -> Option<usize> {
+        leaderboard.submit(
+            table,
+            Entry {
+                score,
+                ..Entry::default()
+            },
+        )
+    }
+
+    #[test]
+    fn keeps_each_table_best_first() {
+        let mut leaderboard = Leaderboard::default();
+        assert_eq!(submit(&mut leaderboard, "normal", 300), Some(0));
+        assert_eq!(submit(&mut leaderboard, "normal", 500), Some(0));
+        assert_eq!(submit(&mut leaderboard, "normal", 400), Some(1));
+        assert_eq!(scores(&leaderboard, "normal"), [500, 400, 300]);
+        assert_eq!(leaderboard.best("normal"), 500);
+    }
+
+    #[test]
+    fn a_tie_ranks_below_the_score_it_ties() {
+        let mut leaderboard = Leaderboard::default();
+        let first = Mutators::from_key("glass");
+        leaderboard.submit(
+            "normal",
+            Entry {
+                score: 100,
+                mutators: first,
+            },
+        );
+        assert_eq!(submit(&mut leaderboard, "normal", 100), Some(1));
+        assert_eq!(leaderboard.entries("normal")[0].mutators, first);
+    }
+
+    #[test]
+    fn truncates_each_table_to_the_best_ten() {
+        let mut leaderboard = Leaderboard::default();
+        for score in 1..=MAX_ENTRIES as u32 {
+            submit(&mut leaderboard, "hard", score * 10);
+        }
+        assert_eq!(submit(&mut leaderboard, "hard", 5), None);
+        assert_eq!(submit(&mut leaderboard, "hard", 10), None);
+        assert_eq!(submit(&mut leaderboard, "hard", 15), Some(9));
+        let hard = scores(&leaderboard, "hard");
+        assert_eq!(hard.len(), MAX_ENTRIES);
+        assert_eq!(hard.first(), Some(&100));
+        assert_eq!(hard.last(), Some(&15));
+    }
+
+    #[test]
+    fn tables_are_ranked_apart() {
+        let mut leaderboard = Leaderboard::default();
+        for score in 1..=MAX_ENTRIES as u32 {
+            submit(&mut leaderboard, "insane", score * 100);
+        }
+        assert_eq!(submit(&mut leaderboard, "easy", 1), Some(0));
+        assert_eq!(scores(&leaderboard, "easy"), [1]);
+        assert_eq!(scores(&leaderboard, "insane").len(), MAX_ENTRIES);
+        assert_eq!(leaderboard.best("daily-2026-10-18"), 0);
+        assert!(leaderboard.entries("daily-2026-10-18").is_empty());
+    }
+}
@@ -0,0 +1,32 @@
+pub mod achievements;
+pub mod adaptive;
+pub mod bot;
+pub mod broadphase;
+pub mod collider;
+pub mod components;
+pub mod daily;
+pub mod difficulty;
+pub mod ecs;
+pub mod effects;
+pub mod enemy;
+pub mod events;
+pub mod focus;
+pub mod hits;
+pub mod leaderboard;
+pub mod lifetime;
+pub mod manifest;
+pub mod modes;
+pub mod mutators;
+pub mod pool;
+pub mod replay;
+pub mod resources;
+pub mod scene;
+pub mod scenes;
+pub mod scoring;
+pub mod settings;
+pub mod sprites;
+pub mod stats;
+pub mod systems;
+pub mod tuning;
+pub mod versus;
+pub mod world;
@@ -0,0 +1,262 @@
+//! Statistics kept across runs, and the run history.
+//!
+//! Lifetime totals are stored as plain text, one `key value` pair per line
+//! like the settings, with per-kind and per-bucket counters under dotted keys
+//! (`kills.small 12`, `scores.500 3`). The history is a CSV file with one
+//! line appended per run, meant for charting in a spreadsheet. Its `end`
+//! column names the enemy the run died to, `friendly`, `finished` or
+//! `abandoned`.
+
+use crate::enemy::EnemyKind;
+use crate::stats::RunStats;
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+
+pub const LIFETIME_PATH: &str = "lifetime.dat";
+pub const HISTORY_PATH: &str = "history.csv";
+
+/// Width of a score histogram bucket, in points.
+pub const SCORE_BUCKET: u32 = 500;
+
+const HISTORY_HEADER: &str =
+    "timestamp,table,seed,score,time,kills,shots_fired,shots_hit,best_chain,end\n";
+
+/// How a run ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunEnd {
+    /// The ships went down, to an enemy or, with `None`, to the other
+    /// player's shot.
+    Died(Option<EnemyKind>),
+    /// The game mode or the versus round timer ended it.
+    Finished,
+    /// Left through the pause menu before it was over.
+    Abandoned,
+}
+
+impl RunEnd {
+    /// Whether the run played out to its game over.
+    pub fn completed(&self) -> bool {
+        *self != RunEnd::Abandoned
+    }
+}
+
+/// One run, as far as the lifetime statistics are concerned.
+pub struct RunRecord<'a> {
+    /// The leaderboard table the run was played for, which names the preset
+    /// and the mode or daily date.
+    pub table: &'a str,
+    pub seed: u64,
+    pub score: u32,
+    pub best_chain: u32,
+    pub stats: &'a RunStats,
+    pub end: RunEnd,
+}
+
+#[derive(Default)]
+pub struct LifetimeStats {
+    /// Seconds spent in runs.
+    pub play_time: f64,
+    pub runs: u32,
+    pub kills: BTreeMap<EnemyKind, u32>,
+    pub shots_fired: u32,
+    pub shots_hit: u32,
+    pub best_chain: u32,
+    pub deaths: BTreeMap<EnemyKind, u32>,
+    /// Deaths to the other player's shots.
+    pub friendly_deaths: u32,
+    /// Runs that reached their game over per score bucket, keyed by the
+    /// bucket's lowest score.
+    pub scores: BTreeMap<u32, u32>,
+}
+
+impl LifetimeStats {
+    pub fn load(path: &str) -> Self {
+        let mut stats = LifetimeStats::default();
+        let Ok(contents) = fs::read_to_string(path) else {
+            return stats;
+        };
+        for line in contents.lines() {
+            let mut parts = line.split_whitespace();
+            let (Some(key), Some(value)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            match key.split_once('.') {
+                None => match key {
+                    "play_time" => stats.play_time = value.parse().unwrap_or(0.0),
+                    "runs" => stats.runs = value.parse().unwrap_or(0),
+                    "shots_fired" => stats.shots_fired = value.parse().unwrap_or(0),
+                    "shots_hit" => stats.shots_hit = value.parse().unwrap_or(0),
+                    "best_chain" => stats.best_chain = value.parse().unwrap_or(0),
+                    "friendly_deaths" => stats.friendly_deaths = value.parse().unwrap_or(0),
+                    _ => {}
+                },
+                Some((group, name)) => {
+                    let Ok(count) = value.parse::<u32>() else {
+                        continue;
+                    };
+                    match (group, EnemyKind::from_key(name), name.parse::<u32>()) {
+                        ("kills", Some(kind), _) => {
+                            stats.kills.insert(kind, count);
+                        }
+                        ("deaths", Some(kind), _) => {
+                            stats.deaths.insert(kind, count);
+                        }
+                        ("scores", _, Ok(bucket)) => {
+                            stats.scores.insert(bucket, count);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        stats
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        let mut contents = format!(
+            "play_time {}\nruns {}\nshots_fired {}\nshots_hit {}\nbest_chain {}\nfriendly_deaths {}\n",
+            self.play_time,
+            self.runs,
+            self.shots_fired,
+            self.shots_hit,
+            self.best_chain,
+            self.friendly_deaths
+        );
+        for (kind, count) in &self.kills {
+            contents.push_str(&format!("kills.{} {}\n", kind.key(), count));
+        }
+        for (kind, count) in &self.deaths {
+            contents.push_str(&format!("deaths.{} {}\n", kind.key(), count));
+        }
+        for (bucket, count) in &self.scores {
+            contents.push_str(&format!("scores.{} {}\n", bucket, count));
+        }
+        fs::write(path, contents)
+    }
+
+    pub fn start_run(&mut self) {
+        self.runs += 1;
+    }
+
+    /// Folds a run into the totals. An abandoned run adds its play time, kills
+    /// and shots, but no score.
+    pub fn record(&mut self, run: &RunRecord) {
+        let stats = run.stats;
+        self.play_time += stats.time_survived as f64;
+        for (&kind, &count) in &stats.kills_by_kind {
+            *self.kills.entry(kind).or_default() += count;
+        }
+        self.shots_fired += stats.shots_fired;
+        self.shots_hit += stats.shots_hit;
+        self.best_chain = self.best_chain.max(run.best_chain);
+        match run.end {
+            RunEnd::Died(Some(kind)) => *self.deaths.entry(kind).or_default() += 1,
+            RunEnd::Died(None) => self.friendly_deaths += 1,
+            RunEnd::Finished | RunEnd::Abandoned => {}
+        }
+        if run.end.completed() {
+            *self
+                .scores
+                .entry(run.score / SCORE_BUCKET * SCORE_BUCKET)
+                .or_default() += 1;
+        }
+    }
+
+    pub fn kills_of(&self, kind: EnemyKind) -> u32 {
+        self.kills.get(&kind).copied().unwrap_or(0)
+    }
+
+    pub fn deaths_to(&self, kind: EnemyKind) -> u32 {
+        self.deaths.get(&kind).copied().unwrap_or(0)
+    }
+}
+
+/// Appends `run` to the history file at `path`, writing the header first if
+/// the file is new.
+pub fn append_history(path: &str, run: &RunRecord) -> std::io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        file.write_all(HISTORY_HEADER.as_bytes())?;
+    }
+    let stats = run.stats;
+    writeln!(
+        file,
+        "{},{},{},{},{:.2},{},{},{},{},{}",
+        macroquad::miniquad::date::now() as u64,
+        run.table,
+        run.seed,
+        run.score,
+        stats.time_survived,
+        stats.kills,
+        stats.shots_fired,
+        stats.shots_hit,
+        run.best_chain,
+        match run.end {
+            RunEnd::Died(Some(kind)) => kind.key(),
+            RunEnd::Died(None) => "friendly",
+            RunEnd::Finished => "finished",
+            RunEnd::Abandoned => "abandoned",
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(stats: &mut LifetimeStats, score: u32, end: RunEnd) {
+        stats.record(&RunRecord {
+            table: "normal",
+            seed: 1,
+            score,
+            best_chain: 0,
+            stats: &RunStats::default(),
+            end,
+        });
+    }
+
+    #[test]
+    fn every_death_counts_with_or_without_a_killer() {
+        let mut stats = LifetimeStats::default();
+        record(&mut stats, 0, RunEnd::Died(Some(EnemyKind::Big)));
+        record(&mut stats, 0, RunEnd::Died(None));
+        record(&mut stats, 0, RunEnd::Finished);
+        assert_eq!(stats.deaths_to(EnemyKind::Big), 1);
+        assert_eq!(stats.friendly_deaths, 1);
+        assert_eq!(stats.deaths.values().sum::<u32>(), 1);
+    }
+
+    #[test]
+    fn scores_fall_into_buckets() {
+        let mut stats = LifetimeStats::default();
+        record(&mut stats, 0, RunEnd::Finished);
+        record(&mut stats, SCORE_BUCKET - 1, RunEnd::Finished);
+        record(&mut stats, SCORE_BUCKET, RunEnd::Finished);
+        assert_eq!(stats.scores.get(&0), Some(&2));
+        assert_eq!(stats.scores.get(&SCORE_BUCKET), Some(&1));
+    }
+
+    #[test]
+    fn abandoned_runs_stay_out_of_the_scores() {
+        let mut stats = LifetimeStats::default();
+        let run_stats = RunStats {
+            time_survived: 30.0,
+            shots_fired: 10,
+            ..RunStats::default()
+        };
+        stats.record(&RunRecord {
+            table: "normal",
+            seed: 1,
+            score: 800,
+            best_chain: 4,
+            stats: &run_stats,
+            end: RunEnd::Abandoned,
+        });
+        assert!(stats.scores.is_empty());
+        assert!(stats.deaths.is_empty());
+        assert_eq!(stats.play_time, 30.0);
+        assert_eq!(stats.shots_fired, 10);
+        assert_eq!(stats.best_chain, 4);
+    }
+}
@@ -0,0 +1,281 @@
+//! The asset manifest, `assets/manifest.toml`.
+//!
+//! The manifest names every file the game loads, under a key the code asks for
+//! it by, and lays out each sprite sheet: its frame size and the animations on
+//! it. Renaming a file or redrawing a sheet is a change to the manifest alone;
+//! `cargo run --bin validate-assets` checks the result against the files.
+
+use macroquad::experimental::animation::{AnimatedSprite, Animation};
+use macroquad::prelude::*;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Relative to the assets folder.
+pub const MANIFEST_FILE: &str = "manifest.toml";
+
+/// The keys `Resources::new` asks for, by section. A manifest without one of
+/// them can't start the game.
+pub const REQUIRED_SPRITES: [&str; 6] = [
+    "ship",
+    "bullet",
+    "explosion",
+    "enemy_small",
+    "enemy_medium",
+    "enemy_big",
+];
+pub const REQUIRED_IMAGES: [&str; 3] = [
+    "window_background",
+    "button_background",
+    "button_clicked_background",
+];
+pub const REQUIRED_SOUNDS: [&str; 3] = ["theme_music", "explosion", "laser"];
+pub const REQUIRED_FONTS: [&str; 1] = ["ui"];
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct AnimationEntry {
+    pub name: String,
+    pub row: u32,
+    pub frames: u32,
+    pub fps: u32,
+}
+
+/// A sprite sheet: a grid of equal frames.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct SpriteEntry {
+    pub file: String,
+    /// Width and height of one frame in pixels.
+    pub frame: [u32; 2],
+    #[serde(default)]
+    pub animations: Vec<AnimationEntry>,
+}
+
+impl SpriteEntry {
+    pub fn frame_size(&self) -> Vec2 {
+        vec2(self.frame[0] as f32, self.frame[1] as f32)
+    }
+
+    pub fn animated_sprite(&self) -> AnimatedSprite {
+        let animations: Vec<Animation> = self
+            .animations
+            .iter()
+            .map(|animation| Animation {
+                name: animation.name.clone(),
+                row: animation.row,
+                frames: animation.frames,
+                fps: animation.fps,
+            })
+            .collect();
+        let [width, height] = self.frame;
+        AnimatedSprite::new(width, height, &animations, true)
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default)]
+    pub sprites: BTreeMap<String, SpriteEntry>,
+    /// Images that aren't sprite sheets, like the UI skin's backgrounds.
+    #[serde(default)]
+    pub images: BTreeMap<String, String>,
+    #[serde(default)]
+    pub sounds: BTreeMap<String, String>,
+    #[serde(default)]
+    pub fonts: BTreeMap<String, String>,
+}
+
+#[derive(Debug)]
+pub enum ManifestError {
+    Read(String),
+    /// Not valid TOML, or not the expected shape.
+    Parse {
+        line: Option<usize>,
+        message: String,
+    },
+    /// Parsed, but an entry makes no sense.
+    Invalid {
+        entry: String,
+        message: String,
+    },
+    /// The game needs a key the manifest doesn't have.
+    Missing {
+        section: &'static str,
+        key: &'static str,
+    },
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ManifestError::Read(error) => write!(f, "can't read {}: {}", MANIFEST_FILE, error),
+            ManifestError::Parse {
+                line: Some(line),
+                message,
+            } => write!(f, "{} line {}: {}", MANIFEST_FILE, line, message),
+            ManifestError::Parse {
+                line: None,
+                message,
+            } => write!(f, "{}: {}", MANIFEST_FILE, message),
+            ManifestError::Invalid { entry, message } => {
+                write!(f, "{}: {} {}", MANIFEST_FILE, entry, message)
+            }
+            ManifestError::Missing { section, key } => {
+                write!(f, "{}: no {}.{}", MANIFEST_FILE, section, key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+impl Manifest {
+    /// Loads the manifest from the assets folder.
+    pub async fn load() -> Result<Manifest, ManifestError> {
+        let text = macroquad::file::load_string(MANIFEST_FILE)
+            .await
+            .map_err(|error| ManifestError::Read(error.to_string()))?;
+        Manifest::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Manifest, ManifestError> {
+        let manifest: Manifest = toml::from_str(text).map_err(|error| ManifestError::Parse {
+            line: error
+                .span()
+                .map(|span| text[..span.start].matches('\n').count() + 1),
+            message: error.message().to_string(),
+        })?;
+        manifest.validate()?;
+        Ok(manifest)
+    }
+
+    /// Checks what can be checked without the files themselves.
+    pub fn validate(&self) -> Result<(), ManifestError> {
+        for (key, sprite) in &self.sprites {
+            if sprite.frame.contains(&0) {
+                return Err(ManifestError::Invalid {
+                    entry: format!("sprites.{}.frame", key),
+                    message: "must be at least 1 pixel each way".to_string(),
+                });
+            }
+            for animation in &sprite.animations {
+                if animation.frames == 0 {
+                    return Err(ManifestError::Invalid {
+                        entry: format!("sprites.{}.animations.{}", key, animation.name),
+                        message: "must have at least 1 frame".to_string(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The manifest in the repository's assets folder, built into the game.
+    /// The simulation runs without loading any assets, so this is where it
+    /// finds the frame sizes its colliders are scaled from.
+    pub fn builtin() -> &'static Manifest {
+        static BUILTIN: OnceLock<Manifest> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            Manifest::parse(include_str!("../assets/manifest.toml"))
+                .expect("the built-in manifest is valid")
+        })
+    }
+
+    /// Every required key the manifest doesn't have.
+    pub fn missing(&self) -> Vec<ManifestError> {
+        let mut missing = Vec::new();
+        missing_keys(&self.sprites, "sprites", &REQUIRED_SPRITES, &mut missing);
+        missing_keys(&self.images, "images", &REQUIRED_IMAGES, &mut missing);
+        missing_keys(&self.sounds, "sounds", &REQUIRED_SOUNDS, &mut missing);
+        missing_keys(&self.fonts, "fonts", &REQUIRED_FONTS, &mut missing);
+        missing
+    }
+
+    pub fn sprite(&self, key: &'static str) -> Result<&SpriteEntry, ManifestError> {
+        self.sprites.get(key).ok_or(ManifestError::Missing {
+            section: "sprites",
+            key,
+        })
+    }
+
+    pub fn image(&self, key: &'static str) -> Result<&str, ManifestError> {
+        lookup(&self.images, "images", key)
+    }
+
+    pub fn sound(&self, key: &'static str) -> Result<&str, ManifestError> {
+        lookup(&self.sounds, "sounds", key)
+    }
+
+    pub fn font(&self, key: &'static str) -> Result<&str, ManifestError> {
+        lookup(&self.fonts, "fonts", key)
+    }
+}
+
+fn lookup<'a>(
+    files: &'a BTreeMap<String, String>,
+    section: &'static str,
+    key: &'static str,
+) -> Result<&'a str, ManifestError> {
+    files
+        .get(key)
+        .map(String::as_str)
+        .ok_or(ManifestError::Missing { section, key })
+}
+
+fn missing_keys<V>(
+    entries: &BTreeMap<String, V>,
+    section: &'static str,
+    required: &[&'static str],
+    missing: &mut Vec<ManifestError>,
+) {
+    for &key in required {
+        if !entries.contains_key(key) {
+            missing.push(ManifestError::Missing { section, key });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sprites;
+
+    #[test]
+    fn the_builtin_manifest_has_every_required_key() {
+        let missing: Vec<String> = Manifest::builtin()
+            .missing()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert!(missing.is_empty(), "{:?}", missing);
+    }
+
+    #[test]
+    fn every_collided_sprite_is_required() {
+        for def in sprites::ALL {
+            assert!(REQUIRED_SPRITES.contains(&def.key), "{}", def.key);
+        }
+    }
+
+    #[test]
+    fn reports_each_missing_key_by_section() {
+        let mut manifest = Manifest::builtin().clone();
+        manifest.sprites.remove("explosion");
+        manifest.sounds.clear();
+        manifest.fonts.remove("ui");
+        let missing: Vec<String> = manifest.missing().iter().map(ToString::to_string).collect();
+        assert_eq!(
+            missing,
+            [
+                "manifest.toml: no sprites.explosion",
+                "manifest.toml: no sounds.theme_music",
+                "manifest.toml: no sounds.explosion",
+                "manifest.toml: no sounds.laser",
+                "manifest.toml: no fonts.ui",
+            ]
+        );
+    }
+}
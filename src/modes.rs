@@ -0,0 +1,176 @@
+//! Game modes.
+//!
+//! A mode is a set of rules layered on top of the simulation: what the player
+//! may do, how the score is kept and when a run ends besides the ship dying.
+//! Modes implement [`GameMode`] and are stateless, so a [`World`] only keeps
+//! the [`ModeKind`] and [`World::step`] asks it for the rules every step.
+
+use crate::difficulty::Preset;
+use crate::world::World;
+
+/// Points per second survived in Pacifist Survival.
+pub const PACIFIST_POINTS_PER_SECOND: f32 = 10.0;
+
+pub trait GameMode: Sync {
+    fn name(&self) -> &'static str;
+
+    /// Stable identifier used for leaderboard tables.
+    fn key(&self) -> &'static str;
+
+    /// Leaderboard table for runs of this mode on `preset`.
+    fn table(&self, preset: Preset) -> String {
+        format!("{}.{}", self.key(), preset.key())
+    }
+
+    fn allows_shooting(&self) -> bool {
+        true
+    }
+
+    /// Runs after the systems every step.
+    fn update(&self, _world: &mut World) {}
+
+    /// Whether the run is over even though the ship is still alive.
+    fn finished(&self, _world: &World) -> bool {
+        false
+    }
+
+    /// The mode's own line on the HUD, if it has one.
+    fn status(&self, _world: &World) -> Option<String> {
+        None
+    }
+}
+
+/// Endless, until the ship is destroyed.
+pub struct Classic;
+
+impl GameMode for Classic {
+    fn name(&self) -> &'static str {
+        "Classic"
+    }
+
+    fn key(&self) -> &'static str {
+        "classic"
+    }
+
+    /// Classic keeps the tables from before there were modes.
+    fn table(&self, preset: Preset) -> String {
+        preset.key().to_string()
+    }
+}
+
+/// Score as much as possible before the clock runs out.
+pub struct TimeAttack {
+    pub seconds: f32,
+    name: &'static str,
+    key: &'static str,
+}
+
+impl GameMode for TimeAttack {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn key(&self) -> &'static str {
+        self.key
+    }
+
+    fn finished(&self, world: &World) -> bool {
+        world.stats.time_survived >= self.seconds
+    }
+
+    fn status(&self, world: &World) -> Option<String> {
+        let left = (self.seconds - world.stats.time_survived).max(0.0).ceil() as u32;
+        Some(format!("Time left {}:{:02}", left / 60, left % 60))
+    }
+}
+
+/// No shooting; the score is the time survived.
+pub struct Pacifist;
+
+impl GameMode for Pacifist {
+    fn name(&self) -> &'static str {
+        "Pacifist Survival"
+    }
+
+    fn key(&self) -> &'static str {
+        "pacifist"
+    }
+
+    fn allows_shooting(&self) -> bool {
+        false
+    }
+
+    fn update(&self, world: &mut World) {
+        world.score = (world.stats.time_survived * PACIFIST_POINTS_PER_SECOND) as u32;
+    }
+
+    fn status(&self, _world: &World) -> Option<String> {
+        Some("No shooting".to_string())
+    }
+}
+
+static CLASSIC: Classic = Classic;
+static TIME_ATTACK_2: TimeAttack = TimeAttack {
+    seconds: 120.0,
+    name: "Time Attack 2:00",
+    key: "time2",
+};
+static TIME_ATTACK_5: TimeAttack = TimeAttack {
+    seconds: 300.0,
+    name: "Time Attack 5:00",
+    key: "time5",
+};
+static PACIFIST: Pacifist = Pacifist;
+
+/// The modes the menu offers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ModeKind {
+    #[default]
+    Classic,
+    TimeAttack2,
+    TimeAttack5,
+    Pacifist,
+}
+
+impl ModeKind {
+    pub const ALL: [ModeKind; 4] = [
+        ModeKind::Classic,
+        ModeKind::TimeAttack2,
+        ModeKind::TimeAttack5,
+        ModeKind::Pacifist,
+    ];
+
+    pub fn from_key(key: &str) -> Option<ModeKind> {
+        ModeKind::ALL
+            .into_iter()
+            .find(|mode| mode.rules().key() == key)
+    }
+
+    pub fn rules(&self) -> &'static dyn GameMode {
+        match self {
+            ModeKind::Classic => &CLASSIC,
+            ModeKind::TimeAttack2 => &TIME_ATTACK_2,
+            ModeKind::TimeAttack5 => &TIME_ATTACK_5,
+            ModeKind::Pacifist => &PACIFIST,
+        }
+    }
+
+    /// Short name that fits on a menu button.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ModeKind::Classic => "Classic",
+            ModeKind::TimeAttack2 => "2 Min",
+            ModeKind::TimeAttack5 => "5 Min",
+            ModeKind::Pacifist => "Pacifist",
+        }
+    }
+
+    pub fn next(&self) -> ModeKind {
+        match self {
+            ModeKind::Classic => ModeKind::TimeAttack2,
+            ModeKind::TimeAttack2 => ModeKind::TimeAttack5,
+            ModeKind::TimeAttack5 => ModeKind::Pacifist,
+            ModeKind::Pacifist => ModeKind::Classic,
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Run mutators.
+//!
+//! Mutators are toggled on the menu before a run and stack freely. Every
+//! mutator changes the run only through [`Mutators::apply`], which adjusts the
+//! [`SimParams`] the world is built with; nothing else in the simulation
+//! checks which mutators are active. The active set is stored with replays
+//! and leaderboard entries as a `+`-separated list of keys.
+
+use crate::world::SimParams;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mutator {
+    /// Every enemy spawns at its kind's largest size.
+    Giants,
+    /// The ship's shots deal double damage, but enemies come half again as
+    /// often and one hit kills the ship, whatever the tuning says.
+    GlassCannon,
+    FastBullets,
+    /// Left and right are swapped.
+    Mirror,
+    /// Only the area around the ship is visible.
+    Fog,
+    /// The ship accelerates and coasts instead of stopping dead.
+    Drift,
+}
+
+impl Mutator {
+    pub const ALL: [Mutator; 6] = [
+        Mutator::Giants,
+        Mutator::GlassCannon,
+        Mutator::FastBullets,
+        Mutator::Mirror,
+        Mutator::Fog,
+        Mutator::Drift,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mutator::Giants => "Giants only",
+            Mutator::GlassCannon => "Glass cannon",
+            Mutator::FastBullets => "Bullet speed x2",
+            Mutator::Mirror => "Mirror controls",
+            Mutator::Fog => "Fog",
+            Mutator::Drift => "Low gravity drift",
+        }
+    }
+
+    /// Short name that fits on a menu button.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Mutator::Giants => "Giants",
+            Mutator::GlassCannon => "Glass",
+            Mutator::FastBullets => "Bullets",
+            Mutator::Mirror => "Mirror",
+            Mutator::Fog => "Fog",
+            Mutator::Drift => "Drift",
+        }
+    }
+
+    /// Stable identifier used in saved data.
+    pub fn key(&self) -> &'static str {
+        match self {
+            Mutator::Giants => "giants",
+            Mutator::GlassCannon => "glass",
+            Mutator::FastBullets => "bullets",
+            Mutator::Mirror => "mirror",
+            Mutator::Fog => "fog",
+            Mutator::Drift => "drift",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Mutator> {
+        Mutator::ALL
+            .into_iter()
+            .find(|mutator| mutator.key() == key)
+    }
+
+    fn bit(&self) -> u8 {
+        1 << *self as u8
+    }
+}
+
+/// A set of active mutators.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Mutators {
+    bits: u8,
+}
+
+impl Mutators {
+    pub fn contains(&self, mutator: Mutator) -> bool {
+        self.bits & mutator.bit() != 0
+    }
+
+    pub fn toggle(&mut self, mutator: Mutator) {
+        self.bits ^= mutator.bit();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Mutator> + '_ {
+        Mutator::ALL
+            .into_iter()
+            .filter(|mutator| self.contains(*mutator))
+    }
+
+    /// The hook every mutator goes through: adjusts the parameters a run is
+    /// simulated with. Mutators are applied in [`Mutator::ALL`] order, each on
+    /// top of the previous ones.
+    pub fn apply(&self, params: &mut SimParams) {
+        for mutator in self.iter() {
+            match mutator {
+                Mutator::Giants => params.giant_enemies = true,
+                Mutator::GlassCannon => {
+                    params.player_hp = 1;
+                    params.bullet_damage *= 2;
+                    params.spawn_rate_scale *= 1.5;
+                }
+                Mutator::FastBullets => params.bullet_speed *= 2.0,
+                Mutator::Mirror => params.mirror_controls = true,
+                Mutator::Fog => params.vision_radius = Some(180.0),
+                Mutator::Drift => params.player_drift = Some(300.0),
+            }
+        }
+    }
+
+    /// Display names, comma-separated.
+    pub fn names(&self) -> String {
+        self.iter()
+            .map(|mutator| mutator.name())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Keys joined with `+`, or `-` when there are none, so the result is
+    /// always one whitespace-free token.
+    pub fn key(&self) -> String {
+        if self.is_empty() {
+            return "-".to_string();
+        }
+        self.iter()
+            .map(|mutator| mutator.key())
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+
+    /// Parses [`Mutators::key`]. Unknown keys are skipped.
+    pub fn from_key(key: &str) -> Self {
+        let mut mutators = Mutators::default();
+        for mutator in key.split('+').filter_map(Mutator::from_key) {
+            mutators.bits |= mutator.bit();
+        }
+        mutators
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mutated(mutator: Mutator, base: SimParams) -> SimParams {
+        let mut mutators = Mutators::default();
+        mutators.toggle(mutator);
+        let mut params = base;
+        mutators.apply(&mut params);
+        params
+    }
+
+    #[test]
+    fn glass_cannon_costs_something() {
+        let base = SimParams::default();
+        let params = mutated(Mutator::GlassCannon, base);
+        assert_eq!(params.bullet_damage, base.bullet_damage * 2);
+        assert!(params.spawn_rate_scale > base.spawn_rate_scale);
+    }
+
+    #[test]
+    fn glass_cannon_overrides_tuned_hit_points() {
+        let base = SimParams {
+            player_hp: 3,
+            ..SimParams::default()
+        };
+        assert_eq!(mutated(Mutator::GlassCannon, base).player_hp, 1);
+    }
+
+    #[test]
+    fn keys_round_trip() {
+        let mut mutators = Mutators::default();
+        assert_eq!(mutators.key(), "-");
+        assert_eq!(Mutators::from_key("-"), mutators);
+        mutators.toggle(Mutator::GlassCannon);
+        mutators.toggle(Mutator::Fog);
+        assert_eq!(mutators.key(), "glass+fog");
+        assert_eq!(Mutators::from_key(&mutators.key()), mutators);
+        assert_eq!(Mutators::from_key("fog+unknown+glass"), mutators);
+    }
+}
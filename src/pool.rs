@@ -0,0 +1,331 @@
+//! Fixed-capacity object pools addressed by generational handles.
+//!
+//! Removing an object only marks its slot free; the value stays in the slot
+//! so the next insert can recycle it (and whatever it allocated) through
+//! [`Pool::insert_with`]. A slot's generation is bumped every time it is
+//! reused, so stale handles to a recycled object simply stop resolving.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+impl Handle {
+    pub(crate) fn new(index: u32, generation: u32) -> Self {
+        Handle { index, generation }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+struct Slot<T> {
+    generation: u32,
+    alive: bool,
+    /// Insertion sequence number, used to find the oldest object.
+    born: u64,
+    value: Option<T>,
+}
+
+pub struct Pool<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    capacity: usize,
+    len: usize,
+    next_born: u64,
+}
+
+/// Pool sizes for the gameplay objects.
+#[derive(Clone, Copy, Debug)]
+pub struct PoolCaps {
+    pub projectiles: usize,
+    pub enemies: usize,
+    pub explosions: usize,
+}
+
+impl Default for PoolCaps {
+    fn default() -> Self {
+        PoolCaps {
+            projectiles: 256,
+            enemies: 256,
+            explosions: 48,
+        }
+    }
+}
+
+impl<T> Pool<T> {
+    pub fn new(capacity: usize) -> Self {
+        Pool {
+            slots: Vec::with_capacity(capacity),
+            free: vec![],
+            capacity,
+            len: 0,
+            next_born: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len >= self.capacity
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn insert(&mut self, value: T) -> Option<Handle> {
+        self.insert_with(|_| value)
+    }
+
+    /// Inserts a value built by `build`, which receives the previous occupant
+    /// of the slot, if any, to recycle. Returns `None` when the pool is full.
+    pub fn insert_with(&mut self, build: impl FnOnce(Option<T>) -> T) -> Option<Handle> {
+        let index = self.vacant_slot()?;
+        Some(self.occupy(index, build))
+    }
+
+    /// Like [`Pool::insert_with`], but when the pool is full the oldest live
+    /// object is evicted and handed to `build` instead of failing. Returns
+    /// `None` only for a pool with no capacity at all.
+    pub fn insert_recycling_with(&mut self, build: impl FnOnce(Option<T>) -> T) -> Option<Handle> {
+        let index = match self.vacant_slot() {
+            Some(index) => index,
+            None => {
+                let oldest = self
+                    .slots
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, slot)| slot.alive)
+                    .min_by_key(|(_, slot)| slot.born)
+                    .map(|(index, _)| index as u32)?;
+                self.slots[oldest as usize].alive = false;
+                self.len -= 1;
+                oldest
+            }
+        };
+        Some(self.occupy(index, build))
+    }
+
+    fn vacant_slot(&mut self) -> Option<u32> {
+        if self.is_full() {
+            return None;
+        }
+        if let Some(index) = self.free.pop() {
+            return Some(index);
+        }
+        self.slots.push(Slot {
+            generation: 0,
+            alive: false,
+            born: 0,
+            value: None,
+        });
+        Some(self.slots.len() as u32 - 1)
+    }
+
+    fn occupy(&mut self, index: u32, build: impl FnOnce(Option<T>) -> T) -> Handle {
+        let born = self.next_born;
+        self.next_born += 1;
+        let slot = &mut self.slots[index as usize];
+        slot.generation = slot.generation.wrapping_add(1);
+        slot.alive = true;
+        slot.born = born;
+        slot.value = Some(build(slot.value.take()));
+        self.len += 1;
+        Handle {
+            index,
+            generation: slot.generation,
+        }
+    }
+
+    fn slot(&self, handle: Handle) -> Option<&Slot<T>> {
+        self.slots
+            .get(handle.index as usize)
+            .filter(|slot| slot.alive && slot.generation == handle.generation)
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.slot(handle).is_some()
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.slot(handle).and_then(|slot| slot.value.as_ref())
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        self.slots
+            .get_mut(handle.index as usize)
+            .filter(|slot| slot.alive && slot.generation == handle.generation)
+            .and_then(|slot| slot.value.as_mut())
+    }
+
+    /// Insertion order of a live object; lower is older.
+    pub fn spawn_order(&self, handle: Handle) -> Option<u64> {
+        self.slot(handle).map(|slot| slot.born)
+    }
+
+    /// Frees the slot. The value is kept around for recycling.
+    pub fn remove(&mut self, handle: Handle) -> bool {
+        if !self.contains(handle) {
+            return false;
+        }
+        self.slots[handle.index as usize].alive = false;
+        self.free.push(handle.index);
+        self.len -= 1;
+        true
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&mut T) -> bool) {
+        for (index, slot) in self.slots.iter_mut().enumerate() {
+            if !slot.alive {
+                continue;
+            }
+            if let Some(value) = slot.value.as_mut() {
+                if !keep(value) {
+                    slot.alive = false;
+                    self.free.push(index as u32);
+                    self.len -= 1;
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.retain(|_| false);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let value = slot.value.as_ref().filter(|_| slot.alive)?;
+            Some((
+                Handle {
+                    index: index as u32,
+                    generation: slot.generation,
+                },
+                value,
+            ))
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle, &mut T)> {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, slot)| {
+                let generation = slot.generation;
+                if !slot.alive {
+                    return None;
+                }
+                let value = slot.value.as_mut()?;
+                Some((
+                    Handle {
+                        index: index as u32,
+                        generation,
+                    },
+                    value,
+                ))
+            })
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.iter().map(|(_, value)| value)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.iter_mut().map(|(_, value)| value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_reused_slot_gets_a_new_generation() {
+        let mut pool = Pool::new(1);
+        let first = pool.insert("first").unwrap();
+        assert!(pool.remove(first));
+        let second = pool.insert("second").unwrap();
+        assert_eq!(second.index(), first.index());
+        assert_ne!(second.generation(), first.generation());
+        assert_eq!(pool.get(second), Some(&"second"));
+    }
+
+    #[test]
+    fn stale_handles_stop_resolving() {
+        let mut pool = Pool::new(1);
+        let stale = pool.insert(1).unwrap();
+        pool.remove(stale);
+        assert!(!pool.contains(stale));
+        assert_eq!(pool.get(stale), None);
+
+        let fresh = pool.insert(2).unwrap();
+        assert!(!pool.contains(stale));
+        assert_eq!(pool.get(stale), None);
+        assert_eq!(pool.get_mut(stale), None);
+        assert_eq!(pool.spawn_order(stale), None);
+        assert!(!pool.remove(stale));
+        assert_eq!(pool.get(fresh), Some(&2));
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn retain_and_clear_leave_stale_handles() {
+        let mut pool = Pool::new(4);
+        let handles: Vec<Handle> = (0..4).map(|n| pool.insert(n).unwrap()).collect();
+        pool.retain(|n| *n % 2 == 0);
+        assert!(pool.contains(handles[0]) && !pool.contains(handles[1]));
+        pool.clear();
+        assert!(pool.is_empty());
+        assert!(handles.iter().all(|&handle| !pool.contains(handle)));
+    }
+
+    #[test]
+    fn a_full_pool_refuses_inserts() {
+        let mut pool = Pool::new(2);
+        pool.insert(1).unwrap();
+        pool.insert(2).unwrap();
+        assert!(pool.is_full());
+        assert_eq!(pool.insert(3), None);
+    }
+
+    #[test]
+    fn recycling_evicts_the_oldest_and_hands_it_over() {
+        let mut pool = Pool::new(2);
+        let oldest = pool.insert(1).unwrap();
+        let newer = pool.insert(2).unwrap();
+        let mut recycled = None;
+        let handle = pool
+            .insert_recycling_with(|old| {
+                recycled = old;
+                3
+            })
+            .unwrap();
+        assert_eq!(recycled, Some(1));
+        assert_eq!(handle.index(), oldest.index());
+        assert!(!pool.contains(oldest));
+        assert_eq!(pool.get(newer), Some(&2));
+        assert_eq!(pool.get(handle), Some(&3));
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[test]
+    fn a_pool_without_capacity_holds_nothing() {
+        let mut pool = Pool::new(0);
+        assert_eq!(pool.insert(1), None);
+        assert_eq!(pool.insert_recycling_with(|_| 1), None);
+        assert!(pool.is_empty());
+    }
+}
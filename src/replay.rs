@@ -0,0 +1,301 @@
+//! Run recordings.
+//!
+//! A [`World`] is deterministic given its seed and what it is fed each step,
+//! so a replay stores just that: the seed, the difficulty, the tuning, the game
+//! mode, the mutators, the co-op rules, the daily challenge if it was one, and
+//! one [`Frame`] per step. Feeding the frames back into a fresh world reproduces the run.
+//!
+//! The last live run's replay is kept in `replay.dat`, as `key value` lines
+//! with one `frame` line per step, followed by the tuning as TOML. Floats are
+//! written in full so that playing the file back reproduces the run exactly.
+
+use crate::daily::{DailyChallenge, Date};
+use crate::difficulty::{Difficulty, Preset};
+use crate::events::EventQueue;
+use crate::modes::ModeKind;
+use crate::mutators::Mutators;
+use crate::tuning::Tuning;
+use crate::versus::Versus;
+use crate::world::{Coop, Input, World, MAX_PLAYERS};
+use macroquad::prelude::*;
+use std::fs;
+
+pub const REPLAY_PATH: &str = "replay.dat";
+
+/// The line the tuning follows, to the end of the file.
+const TUNING_HEADER: &str = "tuning";
+
+/// Everything from outside the simulation that went into one step.
+#[derive(Clone, Copy, Debug)]
+pub struct Frame {
+    /// Input for each ship, by player index.
+    pub inputs: [Input; MAX_PLAYERS],
+    pub delta: f32,
+    pub arena: Vec2,
+    pub spawn_scale: f32,
+    pub speed_scale: f32,
+}
+
+impl Frame {
+    pub fn apply<'a>(&self, world: &'a mut World) -> &'a EventQueue {
+        world.arena = self.arena;
+        world.spawn_scale = self.spawn_scale;
+        world.speed_scale = self.speed_scale;
+        world.step(&self.inputs, self.delta)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Replay {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    /// The tuning the run was played with, which a reload since then must
+    /// not change.
+    pub tuning: Tuning,
+    pub mode: ModeKind,
+    pub mutators: Mutators,
+    pub coop: Option<Coop>,
+    pub versus: Option<Versus>,
+    pub daily: Option<DailyChallenge>,
+    pub frames: Vec<Frame>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, difficulty: Difficulty, tuning: Tuning) -> Self {
+        Replay {
+            seed,
+            difficulty,
+            tuning,
+            mode: ModeKind::Classic,
+            mutators: Mutators::default(),
+            coop: None,
+            versus: None,
+            daily: None,
+            frames: vec![],
+        }
+    }
+
+    pub fn record(&mut self, frame: Frame) {
+        self.frames.push(frame);
+    }
+
+    pub fn load(path: &str) -> Option<Self> {
+        Replay::parse(&fs::read_to_string(path).ok()?)
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "seed {}\npreset {}\nmode {}\nmutators {}\n",
+            self.seed,
+            self.difficulty.preset.key(),
+            self.mode.rules().key(),
+            self.mutators.key()
+        );
+        if let Some(coop) = self.coop {
+            text.push_str(&format!(
+                "coop {} {}\n",
+                coop.shared_lives as u8, coop.friendly_fire as u8
+            ));
+        }
+        if let Some(versus) = self.versus {
+            text.push_str(&format!("versus {}\n", versus.garbage as u8));
+        }
+        if let Some(daily) = &self.daily {
+            text.push_str(&format!("daily {}\n", daily.date));
+        }
+        for frame in &self.frames {
+            text.push_str("frame");
+            for input in &frame.inputs {
+                text.push_str(&format!(
+                    " {} {} {}",
+                    input.movement.x, input.movement.y, input.fire as u8
+                ));
+            }
+            text.push_str(&format!(
+                " {} {} {} {} {}\n",
+                frame.delta, frame.arena.x, frame.arena.y, frame.spawn_scale, frame.speed_scale
+            ));
+        }
+        text.push_str(TUNING_HEADER);
+        text.push('\n');
+        text.push_str(&toml::to_string(&self.tuning).expect("the tuning is plain data"));
+        text
+    }
+
+    /// Parses [`Replay::to_text`]. `None` for anything malformed, since a
+    /// replay that is even slightly off plays back a different run.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (header, tuning) = text.split_once(&format!("\n{}\n", TUNING_HEADER))?;
+        let mut replay = Replay::new(0, Preset::Normal.difficulty(), Tuning::parse(tuning).ok()?);
+        let mut preset = None;
+        for line in header.lines() {
+            let (key, value) = line.split_once(' ')?;
+            let mut values = value.split(' ');
+            match key {
+                "seed" => replay.seed = value.parse().ok()?,
+                "preset" => preset = Some(Preset::from_key(value)?),
+                "mode" => replay.mode = ModeKind::from_key(value)?,
+                "mutators" => replay.mutators = Mutators::from_key(value),
+                "coop" => {
+                    replay.coop = Some(Coop {
+                        shared_lives: flag(values.next()?)?,
+                        friendly_fire: flag(values.next()?)?,
+                    })
+                }
+                "versus" => {
+                    replay.versus = Some(Versus {
+                        garbage: flag(value)?,
+                    })
+                }
+                "daily" => replay.daily = Some(DailyChallenge::for_date(Date::parse(value)?)),
+                "frame" => {
+                    let mut number = || values.next()?.parse::<f32>().ok();
+                    let mut inputs = [Input::default(); MAX_PLAYERS];
+                    for input in &mut inputs {
+                        input.movement = vec2(number()?, number()?);
+                        input.fire = number()? != 0.0;
+                    }
+                    replay.record(Frame {
+                        inputs,
+                        delta: number()?,
+                        arena: vec2(number()?, number()?),
+                        spawn_scale: number()?,
+                        speed_scale: number()?,
+                    });
+                    if values.next().is_some() {
+                        return None;
+                    }
+                }
+                _ => return None,
+            }
+        }
+        replay.difficulty = match &replay.daily {
+            Some(daily) => daily.difficulty(),
+            None => preset?.difficulty(),
+        };
+        Some(replay)
+    }
+}
+
+fn flag(value: &str) -> Option<bool> {
+    match value {
+        "0" => Some(false),
+        "1" => Some(true),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot::Bot;
+    use crate::events::Event;
+    use crate::pool::PoolCaps;
+    use crate::world::{Controller, SimParams};
+
+    fn world(replay: &Replay) -> World {
+        let mut params = SimParams::default();
+        replay.tuning.apply(&mut params);
+        replay.mutators.apply(&mut params);
+        params.coop = replay.coop;
+        World::new(
+            vec2(800.0, 600.0),
+            replay.difficulty,
+            PoolCaps::default(),
+            replay.seed,
+            params,
+        )
+    }
+
+    /// Plays `replay` back, returning the score and the time it ran.
+    fn play_back(replay: &Replay) -> (u32, f32) {
+        let mut world = world(replay);
+        for frame in &replay.frames {
+            frame.apply(&mut world);
+        }
+        (world.score, world.stats.time_survived)
+    }
+
+    /// Half a minute flown by the bot with one ship and a ragged frame rate.
+    fn recorded() -> Replay {
+        let mut replay = Replay {
+            mutators: Mutators::from_key("giants+bullets"),
+            coop: Some(Coop {
+                shared_lives: true,
+                friendly_fire: false,
+            }),
+            ..Replay::new(7, Preset::Hard.difficulty(), Tuning::default())
+        };
+        let mut world = world(&replay);
+        let mut bot = Bot::default();
+        let ship = world.ships[0];
+        for step in 0..1800 {
+            let mut inputs = [Input::default(); MAX_PLAYERS];
+            inputs[0] = bot.input(&world, ship);
+            let frame = Frame {
+                inputs,
+                delta: 1.0 / 60.0 + (step % 7) as f32 * 0.0013,
+                arena: vec2(800.0, 600.0),
+                spawn_scale: 1.0,
+                speed_scale: 1.0 + (step / 600) as f32 * 0.05,
+            };
+            replay.record(frame);
+            let events = frame.apply(&mut world);
+            if events
+                .iter()
+                .any(|event| matches!(event, Event::RunEnded { .. }))
+            {
+                break;
+            }
+        }
+        replay
+    }
+
+    #[test]
+    fn round_trips_through_text() {
+        let replay = recorded();
+        let text = replay.to_text();
+        let parsed = Replay::parse(&text).expect("a saved replay parses");
+        assert_eq!(parsed.to_text(), text);
+        assert_eq!(parsed.difficulty.preset, Preset::Hard);
+        assert_eq!(parsed.frames.len(), replay.frames.len());
+    }
+
+    #[test]
+    fn a_parsed_replay_plays_the_same_run() {
+        let replay = recorded();
+        let parsed = Replay::parse(&replay.to_text()).unwrap();
+        let (score, time) = play_back(&replay);
+        assert!(score > 0);
+        assert_eq!(play_back(&parsed), (score, time));
+    }
+
+    #[test]
+    fn a_daily_takes_its_challenge_from_the_date() {
+        let daily = DailyChallenge::for_date(Date::parse("2026-10-18").unwrap());
+        let replay = Replay {
+            daily: Some(daily.clone()),
+            ..Replay::new(daily.seed, daily.difficulty(), Tuning::default())
+        };
+        let parsed = Replay::parse(&replay.to_text()).unwrap();
+        assert_eq!(parsed.daily.map(|parsed| parsed.date), Some(daily.date));
+        assert_eq!(parsed.seed, daily.seed);
+        assert_eq!(parsed.difficulty.preset, daily.difficulty().preset);
+    }
+
+    #[test]
+    fn anything_malformed_is_refused() {
+        let text = recorded().to_text();
+        assert!(Replay::parse(&text.replacen("preset hard", "preset nightmare", 1)).is_none());
+        assert!(Replay::parse(&text.replacen("seed 7", "seed -7", 1)).is_none());
+        assert!(Replay::parse(&text.replacen("coop 1 0", "coop 1", 1)).is_none());
+        assert!(Replay::parse(&text.replacen("\ntuning\n", "\n", 1)).is_none());
+        assert!(Replay::parse(&text.replacen("\nframe ", "\nframe 1 ", 1)).is_none());
+        assert!(Replay::parse(&text.replacen("\nframe ", "\nframe x ", 1)).is_none());
+        assert!(Replay::parse("").is_none());
+    }
+}
@@ -0,0 +1,477 @@
+//! Textures, sounds, shaders and the UI skin, loaded once at startup from the
+//! files the asset manifest names.
+
+use crate::effects::Sfx;
+use crate::enemy::EnemyKind;
+use crate::manifest::{Manifest, ManifestError, SpriteEntry};
+use macroquad::audio::{load_sound_from_bytes, Sound};
+use macroquad::experimental::animation::AnimatedSprite;
+use macroquad::experimental::collections::storage;
+use macroquad::experimental::coroutines::start_coroutine;
+use macroquad::prelude::*;
+use macroquad::ui::{root_ui, Skin, StyleBuilder};
+use std::fmt;
+
+const STARFIELD_FRAGMENT_SHADER: &str = include_str!("starfield-shader.glsl");
+
+const STARFIELD_VERTEX_SHADER: &str = "#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+varying float iTime;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+uniform vec4 _Time;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    iTime = _Time.x;
+}
+";
+
+/// Swaps the red and blue channels, turning the ship's palette from warm to
+/// cool.
+fn palette_swap(mut image: Image) -> Image {
+    for pixel in image.get_image_data_mut() {
+        pixel.swap(0, 2);
+    }
+    image
+}
+
+/// Why an asset couldn't be loaded, in words for the error screen.
+#[derive(Debug)]
+pub enum AssetError {
+    Manifest(ManifestError),
+    /// A file the manifest names couldn't be loaded.
+    File {
+        file: String,
+        reason: String,
+    },
+    Shader(String),
+}
+
+impl AssetError {
+    fn file(file: &str) -> impl FnOnce(macroquad::Error) -> AssetError + '_ {
+        move |error| AssetError::File {
+            file: file.to_string(),
+            reason: describe(&error),
+        }
+    }
+}
+
+impl fmt::Display for AssetError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AssetError::Manifest(error) => error.fmt(f),
+            AssetError::File { file, reason } => write!(f, "{}: {}", file, reason),
+            AssetError::Shader(reason) => write!(f, "starfield shader: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for AssetError {}
+
+impl From<ManifestError> for AssetError {
+    fn from(error: ManifestError) -> Self {
+        AssetError::Manifest(error)
+    }
+}
+
+/// macroquad's own messages are its debug output; this is just the reason.
+fn describe(error: &macroquad::Error) -> String {
+    match error {
+        macroquad::Error::FileError { kind, .. } => kind.to_string(),
+        macroquad::Error::ImageError(error) => error.to_string(),
+        macroquad::Error::ShaderError(error) => error.to_string(),
+        macroquad::Error::FontError(reason) | macroquad::Error::UnknownError(reason) => {
+            reason.to_string()
+        }
+    }
+}
+
+/// Decodes a whole sound file the way the audio backend will, which panics
+/// on anything it can't play.
+pub fn check_sound(bytes: &[u8]) -> Result<(), String> {
+    let mut reader =
+        audrey::Reader::new(std::io::Cursor::new(bytes)).map_err(|error| error.to_string())?;
+    let channels = reader.description().channel_count();
+    if channels != 1 && channels != 2 {
+        return Err(format!("{} channels, only mono and stereo play", channels));
+    }
+    match reader.samples::<f32>().find_map(Result::err) {
+        Some(error) => Err(error.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// A sprite sheet and its layout from the manifest.
+pub struct SpriteSheet {
+    pub texture: Texture2D,
+    pub layout: SpriteEntry,
+}
+
+impl SpriteSheet {
+    fn new(layout: SpriteEntry, image: &Image) -> SpriteSheet {
+        let texture = Texture2D::from_image(image);
+        texture.set_filter(FilterMode::Nearest);
+        SpriteSheet { texture, layout }
+    }
+
+    async fn load(
+        manifest: &Manifest,
+        key: &'static str,
+        failures: &mut Vec<AssetError>,
+    ) -> Result<SpriteSheet, AssetError> {
+        let (layout, image) = sheet_image(manifest, key, failures).await?;
+        Ok(SpriteSheet::new(layout, &image))
+    }
+
+    pub fn animated_sprite(&self) -> AnimatedSprite {
+        self.layout.animated_sprite()
+    }
+
+    /// Frames across the sheet.
+    pub fn columns(&self) -> u32 {
+        self.texture.width() as u32 / self.layout.frame[0]
+    }
+
+    /// Frames down the sheet.
+    pub fn rows(&self) -> u32 {
+        self.texture.height() as u32 / self.layout.frame[1]
+    }
+}
+
+/// A sheet of plain magenta frames with room for every animation of
+/// `layout`, standing in for one that failed to load.
+fn placeholder_sheet(layout: &SpriteEntry) -> Image {
+    let animations = &layout.animations;
+    let columns = animations.iter().map(|animation| animation.frames).max();
+    let rows = animations.iter().map(|animation| animation.row + 1).max();
+    let [width, height] = layout.frame;
+    Image::gen_image_color(
+        (width * columns.unwrap_or(1)) as u16,
+        (height * rows.unwrap_or(1)) as u16,
+        MAGENTA,
+    )
+}
+
+/// A sheet's layout and image. Only the layout is critical: a file that
+/// fails to load is replaced by a placeholder.
+async fn sheet_image(
+    manifest: &Manifest,
+    key: &'static str,
+    failures: &mut Vec<AssetError>,
+) -> Result<(SpriteEntry, Image), AssetError> {
+    let layout = manifest.sprite(key)?.clone();
+    let image = match load_image(&layout.file).await {
+        Ok(image) => image,
+        Err(error) => {
+            failures.push(AssetError::file(&layout.file)(error));
+            placeholder_sheet(&layout)
+        }
+    };
+    Ok((layout, image))
+}
+
+/// A UI image, or a plain `fallback` coloured one if it fails to load.
+async fn image(
+    manifest: &Manifest,
+    key: &'static str,
+    fallback: Color,
+    failures: &mut Vec<AssetError>,
+) -> Result<Image, AssetError> {
+    let file = manifest.image(key)?;
+    Ok(match load_image(file).await {
+        Ok(image) => image,
+        Err(error) => {
+            failures.push(AssetError::file(file)(error));
+            Image::gen_image_color(128, 128, fallback)
+        }
+    })
+}
+
+/// A sound, or `None` to stay silent if it fails to load.
+async fn sound(
+    manifest: &Manifest,
+    key: &'static str,
+    failures: &mut Vec<AssetError>,
+) -> Result<Option<Sound>, AssetError> {
+    let file = manifest.sound(key)?;
+    let bytes = match load_file(file).await {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            failures.push(AssetError::file(file)(error));
+            return Ok(None);
+        }
+    };
+    // The browser decodes sounds itself, and reports what it can't.
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Err(reason) = check_sound(&bytes) {
+        failures.push(AssetError::File {
+            file: file.to_string(),
+            reason,
+        });
+        return Ok(None);
+    }
+    match load_sound_from_bytes(&bytes).await {
+        Ok(sound) => Ok(Some(sound)),
+        Err(error) => {
+            failures.push(AssetError::file(file)(error));
+            Ok(None)
+        }
+    }
+}
+
+/// The UI font, or `None` to keep macroquad's own if it fails to load.
+async fn font(
+    manifest: &Manifest,
+    failures: &mut Vec<AssetError>,
+) -> Result<Option<Vec<u8>>, AssetError> {
+    let file = manifest.font("ui")?;
+    let font = load_file(file).await.and_then(|bytes| {
+        // Parse it once here, so the styles below can't fail to.
+        root_ui().style_builder().font(&bytes)?;
+        Ok(bytes)
+    });
+    Ok(match font {
+        Ok(bytes) => Some(bytes),
+        Err(error) => {
+            failures.push(AssetError::file(file)(error));
+            None
+        }
+    })
+}
+
+/// Splits `text` into lines no wider than `width` at `font_size`, breaking
+/// between words.
+fn wrap(text: &str, font_size: u16, width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", line, word)
+            };
+            if !line.is_empty() && measure_text(&candidate, None, font_size, 1.0).width > width {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Lists the assets that failed to load until a key is pressed. Drawn with
+/// macroquad's own font, since the UI's may be what failed.
+async fn show_failures(title: &str, failures: &[String], hint: &str) {
+    const MARGIN: f32 = 40.0;
+    loop {
+        clear_background(BLACK);
+        draw_text(title, MARGIN, 70.0, 40.0, RED);
+        let mut y = 120.0;
+        for failure in failures {
+            for line in wrap(failure, 24, screen_width() - MARGIN * 2.0) {
+                draw_text(&line, MARGIN, y, 24.0, WHITE);
+                y += 26.0;
+            }
+            y += 8.0;
+        }
+        draw_text(hint, MARGIN, screen_height() - 40.0, 24.0, GRAY);
+        if get_last_key_pressed().is_some()
+            || is_mouse_button_pressed(MouseButton::Left)
+            || is_quit_requested()
+        {
+            return;
+        }
+        next_frame().await;
+    }
+}
+
+pub struct Resources {
+    pub ship: SpriteSheet,
+    /// The ship recoloured for the second player.
+    pub second_ship_texture: Texture2D,
+    pub bullet: SpriteSheet,
+    pub explosion: SpriteSheet,
+    pub enemy_small: SpriteSheet,
+    pub enemy_medium: SpriteSheet,
+    pub enemy_big: SpriteSheet,
+    /// Sounds are `None` when they failed to load, and the game is silent
+    /// where they would play.
+    pub theme_music: Option<Sound>,
+    pub sound_explosion: Option<Sound>,
+    pub sound_laser: Option<Sound>,
+    pub ui_skin: Skin,
+    pub starfield_material: Material,
+    pub starfield_target: RenderTarget,
+}
+
+impl Resources {
+    /// Loads everything the manifest names. Files that fail to load are
+    /// replaced by placeholders or silence and added to `failures`; the error
+    /// is for what the game can't run without, like the manifest itself.
+    pub async fn new(failures: &mut Vec<AssetError>) -> Result<Resources, AssetError> {
+        let manifest = Manifest::load().await?;
+        let (ship_layout, ship_image) = sheet_image(&manifest, "ship", failures).await?;
+        let ship = SpriteSheet::new(ship_layout, &ship_image);
+        let second_ship_texture = Texture2D::from_image(&palette_swap(ship_image));
+        second_ship_texture.set_filter(FilterMode::Nearest);
+        let bullet = SpriteSheet::load(&manifest, "bullet", failures).await?;
+        let explosion = SpriteSheet::load(&manifest, "explosion", failures).await?;
+        let enemy_small = SpriteSheet::load(&manifest, "enemy_small", failures).await?;
+        let enemy_medium = SpriteSheet::load(&manifest, "enemy_medium", failures).await?;
+        let enemy_big = SpriteSheet::load(&manifest, "enemy_big", failures).await?;
+        build_textures_atlas();
+
+        let theme_music = sound(&manifest, "theme_music", failures).await?;
+        let sound_explosion = sound(&manifest, "explosion", failures).await?;
+        let sound_laser = sound(&manifest, "laser", failures).await?;
+
+        let window_background = image(&manifest, "window_background", DARKBLUE, failures).await?;
+        let button_background = image(&manifest, "button_background", GRAY, failures).await?;
+        let button_clicked_background =
+            image(&manifest, "button_clicked_background", DARKGRAY, failures).await?;
+        let font = font(&manifest, failures).await?;
+
+        let window_style = root_ui()
+            .style_builder()
+            .background(window_background)
+            .background_margin(RectOffset::new(32.0, 76.0, 44.0, 20.0))
+            .margin(RectOffset::new(0.0, -40.0, 0.0, 0.0))
+            .build();
+        let with_font = |builder: StyleBuilder| match &font {
+            Some(font) => builder.font(font).expect("the font parsed when loaded"),
+            None => builder,
+        };
+        let button_style = with_font(root_ui().style_builder())
+            .background(button_background)
+            .background_clicked(button_clicked_background)
+            .background_margin(RectOffset::new(16.0, 16.0, 16.0, 16.0))
+            .margin(RectOffset::new(16.0, 0.0, -8.0, -8.0))
+            .text_color(WHITE)
+            .font_size(64)
+            .build();
+        let label_style = with_font(root_ui().style_builder())
+            .text_color(WHITE)
+            .font_size(28)
+            .build();
+
+        let starfield_target = render_target(320, 150);
+        starfield_target.texture.set_filter(FilterMode::Nearest);
+        let starfield_material = load_material(
+            ShaderSource::Glsl {
+                vertex: STARFIELD_VERTEX_SHADER,
+                fragment: STARFIELD_FRAGMENT_SHADER,
+            },
+            MaterialParams {
+                uniforms: vec![
+                    UniformDesc::new("iResolution".to_owned().as_str(), UniformType::Float2),
+                    UniformDesc::new(
+                        "direction_modifier".to_owned().as_str(),
+                        UniformType::Float1,
+                    ),
+                ],
+                ..Default::default()
+            },
+        )
+        .map_err(|error| AssetError::Shader(describe(&error)))?;
+
+        let ui_skin = Skin {
+            window_style,
+            button_style,
+            label_style,
+            ..root_ui().default_skin()
+        };
+
+        Ok(Resources {
+            ship,
+            second_ship_texture,
+            bullet,
+            explosion,
+            enemy_small,
+            enemy_medium,
+            enemy_big,
+            theme_music,
+            sound_explosion,
+            sound_laser,
+            ui_skin,
+            starfield_material,
+            starfield_target,
+        })
+    }
+
+    pub fn sfx(&self, sfx: Sfx) -> Option<&Sound> {
+        match sfx {
+            Sfx::Laser => self.sound_laser.as_ref(),
+            Sfx::Explosion => self.sound_explosion.as_ref(),
+        }
+    }
+
+    pub fn enemy(&self, kind: EnemyKind) -> &SpriteSheet {
+        match kind {
+            EnemyKind::Small => &self.enemy_small,
+            EnemyKind::Medium => &self.enemy_medium,
+            EnemyKind::Big => &self.enemy_big,
+        }
+    }
+
+    /// Loads everything behind a loading screen, then lists anything that
+    /// failed to load until the player has read it. The error is what kept
+    /// the game from starting.
+    pub async fn load() -> Result<(), AssetError> {
+        let resources_loading = start_coroutine(async move {
+            let mut failures = Vec::new();
+            let resources = Resources::new(&mut failures).await;
+            (resources, failures)
+        });
+
+        while !resources_loading.is_done() {
+            clear_background(BLACK);
+            let text = format!(
+                "Loading resources {}",
+                ".".repeat(((get_time() * 2.) as usize) % 4)
+            );
+            draw_text(
+                &text,
+                screen_width() / 2. - 160.,
+                screen_height() / 2.,
+                40.,
+                WHITE,
+            );
+            next_frame().await;
+        }
+
+        let (resources, failures) = resources_loading
+            .retrieve()
+            .expect("the loading coroutine is done");
+        let mut lines: Vec<String> = failures.iter().map(ToString::to_string).collect();
+        for line in &lines {
+            warn!("{}", line);
+        }
+        match resources {
+            Ok(resources) => {
+                storage::store(resources);
+                if !lines.is_empty() {
+                    show_failures(
+                        "Some assets failed to load",
+                        &lines,
+                        "Press any key to play without them",
+                    )
+                    .await;
+                }
+                Ok(())
+            }
+            Err(error) => {
+                error!("{}", error);
+                lines.insert(0, error.to_string());
+                show_failures("The game can't start", &lines, "Press any key to quit").await;
+                Err(error)
+            }
+        }
+    }
+}
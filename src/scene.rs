@@ -0,0 +1,169 @@
+//! Scenes and the scene stack.
+//!
+//! Only the top scene is updated. Overlays (pause, options, confirmations)
+//! are drawn on top of the scenes below them, which stay frozen until the
+//! overlay is popped. Replacing the whole stack fades the screen out and the
+//! new scene in.
+
+use crate::scenes::Context;
+use macroquad::prelude::*;
+
+/// Seconds each half of a fade takes.
+const FADE_TIME: f32 = 0.25;
+
+pub trait Scene {
+    fn on_enter(&mut self, _ctx: &mut Context) {}
+
+    fn on_exit(&mut self, _ctx: &mut Context) {}
+
+    /// Advances the scene, which is the top of the stack, by `delta` seconds.
+    fn update(&mut self, ctx: &mut Context, delta: f32) -> Transition;
+
+    fn draw(&mut self, ctx: &Context);
+
+    /// Called on the top scene when the window loses focus or, on the web,
+    /// the tab is hidden.
+    fn on_focus_lost(&mut self, _ctx: &mut Context) -> Transition {
+        Transition::None
+    }
+
+    /// Whether the scenes below stay visible under this one.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+pub enum Transition {
+    None,
+    /// Puts a scene on top of the current one.
+    Push(Box<dyn Scene>),
+    /// Returns to the scene below.
+    Pop,
+    /// Replaces the whole stack with a single scene, fading in between.
+    Switch(Box<dyn Scene>),
+    Quit,
+}
+
+enum Fade {
+    None,
+    Out { timer: f32, next: Box<dyn Scene> },
+    In { timer: f32 },
+}
+
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+    fade: Fade,
+    quit: bool,
+}
+
+impl SceneStack {
+    pub fn new(ctx: &mut Context, mut first: Box<dyn Scene>) -> Self {
+        first.on_enter(ctx);
+        SceneStack {
+            scenes: vec![first],
+            fade: Fade::In { timer: 0.0 },
+            quit: false,
+        }
+    }
+
+    /// Whether a scene asked to quit the game.
+    pub fn quit(&self) -> bool {
+        self.quit
+    }
+
+    pub fn update(&mut self, ctx: &mut Context, delta: f32) {
+        match &mut self.fade {
+            Fade::None => {}
+            Fade::In { timer } => {
+                *timer += delta;
+                if *timer >= FADE_TIME {
+                    self.fade = Fade::None;
+                }
+            }
+            Fade::Out { timer, .. } => {
+                *timer += delta;
+                if *timer >= FADE_TIME {
+                    let Fade::Out { next, .. } = std::mem::replace(&mut self.fade, Fade::None)
+                    else {
+                        unreachable!();
+                    };
+                    self.clear(ctx);
+                    self.push(ctx, next);
+                    self.fade = Fade::In { timer: 0.0 };
+                }
+                // Nothing reacts to input while the screen goes dark.
+                return;
+            }
+        }
+
+        let Some(top) = self.scenes.last_mut() else {
+            return;
+        };
+        let transition = top.update(ctx, delta);
+        self.apply(ctx, transition);
+    }
+
+    /// Lets the top scene react to the window losing focus.
+    pub fn focus_lost(&mut self, ctx: &mut Context) {
+        if matches!(self.fade, Fade::Out { .. }) {
+            return;
+        }
+        if let Some(top) = self.scenes.last_mut() {
+            let transition = top.on_focus_lost(ctx);
+            self.apply(ctx, transition);
+        }
+    }
+
+    fn apply(&mut self, ctx: &mut Context, transition: Transition) {
+        match transition {
+            Transition::None => {}
+            Transition::Push(scene) => self.push(ctx, scene),
+            Transition::Pop => {
+                if let Some(mut scene) = self.scenes.pop() {
+                    scene.on_exit(ctx);
+                }
+            }
+            Transition::Switch(next) => self.fade = Fade::Out { timer: 0.0, next },
+            Transition::Quit => self.quit = true,
+        }
+    }
+
+    /// Exits every scene, top first, so each can save what it needs to.
+    pub fn clear(&mut self, ctx: &mut Context) {
+        while let Some(mut scene) = self.scenes.pop() {
+            scene.on_exit(ctx);
+        }
+    }
+
+    fn push(&mut self, ctx: &mut Context, mut scene: Box<dyn Scene>) {
+        scene.on_enter(ctx);
+        self.scenes.push(scene);
+    }
+
+    /// Draws the top scene and, under overlays, every scene it covers.
+    pub fn draw(&mut self, ctx: &Context) {
+        let first_visible = self
+            .scenes
+            .iter()
+            .rposition(|scene| !scene.is_overlay())
+            .unwrap_or(0);
+        for scene in &mut self.scenes[first_visible..] {
+            scene.draw(ctx);
+        }
+
+        let darkness = match &self.fade {
+            Fade::None => 0.0,
+            Fade::Out { timer, .. } => timer / FADE_TIME,
+            Fade::In { timer } => 1.0 - timer / FADE_TIME,
+        };
+        if darkness > 0.0 {
+            draw_rectangle(
+                0.0,
+                0.0,
+                screen_width(),
+                screen_height(),
+                Color::new(0.0, 0.0, 0.0, darkness.clamp(0.0, 1.0)),
+            );
+        }
+    }
+}
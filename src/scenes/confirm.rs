@@ -0,0 +1,69 @@
+use super::{centered, dim_background, Context, Gameplay, MainMenu};
+use crate::scene::{Scene, Transition};
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+/// What a [`Confirm`] overlay does when the player says yes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfirmAction {
+    Quit,
+    /// Abandons the current run and starts a new one.
+    Restart,
+    /// Abandons the current run and returns to the main menu.
+    MainMenu,
+}
+
+impl ConfirmAction {
+    /// The window title and its horizontal offset.
+    fn title(self) -> (&'static str, f32) {
+        match self {
+            ConfirmAction::Quit => ("Quit?", 185.0),
+            ConfirmAction::Restart => ("Restart?", 155.0),
+            ConfirmAction::MainMenu => ("Abandon run?", 110.0),
+        }
+    }
+}
+
+/// Asks before doing something that can't be undone.
+pub struct Confirm {
+    action: ConfirmAction,
+}
+
+impl Confirm {
+    pub fn new(action: ConfirmAction) -> Self {
+        Confirm { action }
+    }
+}
+
+impl Scene for Confirm {
+    fn update(&mut self, ctx: &mut Context, _delta: f32) -> Transition {
+        let window_size = vec2(500.0, 320.0);
+        let (title, title_x) = self.action.title();
+        let mut transition = Transition::None;
+        root_ui().window(hash!(), centered(window_size), window_size, |ui| {
+            ui.label(vec2(title_x, -34.0), title);
+            if ui.button(vec2(160.0, 25.0), "Yes") {
+                transition = match self.action {
+                    ConfirmAction::Quit => Transition::Quit,
+                    ConfirmAction::Restart => Transition::Switch(Box::new(Gameplay::new(ctx))),
+                    ConfirmAction::MainMenu => Transition::Switch(Box::new(MainMenu::new())),
+                };
+            }
+            if ui.button(vec2(175.0, 125.0), "No") {
+                transition = Transition::Pop;
+            }
+        });
+        if is_key_pressed(KeyCode::Escape) {
+            transition = Transition::Pop;
+        }
+        transition
+    }
+
+    fn draw(&mut self, _ctx: &Context) {
+        dim_background();
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,64 @@
+use super::{centered, Context};
+use crate::achievements::ACHIEVEMENTS;
+use crate::scene::{Scene, Transition};
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+/// Every achievement, locked or not.
+pub struct Gallery;
+
+impl Scene for Gallery {
+    fn update(&mut self, _ctx: &mut Context, _delta: f32) -> Transition {
+        let window_size = vec2(300.0, 120.0);
+        let position = vec2(
+            centered(window_size).x,
+            screen_height() - window_size.y - 20.0,
+        );
+        let mut transition = Transition::None;
+        root_ui().window(hash!(), position, window_size, |ui| {
+            if ui.button(vec2(55.0, 5.0), "Back") {
+                transition = Transition::Pop;
+            }
+        });
+        if is_key_pressed(KeyCode::Escape) {
+            transition = Transition::Pop;
+        }
+        transition
+    }
+
+    fn draw(&mut self, ctx: &Context) {
+        clear_background(BLACK);
+        let achievements = &ctx.achievements;
+        let unlocked = ACHIEVEMENTS
+            .iter()
+            .filter(|achievement| achievements.is_unlocked(achievement))
+            .count();
+        draw_text(
+            format!("Achievements {}/{}", unlocked, ACHIEVEMENTS.len()).as_str(),
+            40.0,
+            60.0,
+            50.0,
+            WHITE,
+        );
+        for (row, achievement) in ACHIEVEMENTS.iter().enumerate() {
+            let y = 110.0 + row as f32 * 40.0;
+            let (color, status) = if achievements.is_unlocked(achievement) {
+                (YELLOW, "Unlocked".to_string())
+            } else if let Some((current, target)) = achievements.progress(achievement) {
+                (GRAY, format!("{}/{}", current, target))
+            } else {
+                (GRAY, "Locked".to_string())
+            };
+            draw_text(achievement.name, 40.0, y, 28.0, color);
+            draw_text(achievement.description, 40.0, y + 18.0, 18.0, GRAY);
+            let status_dimensions = measure_text(&status, None, 25, 1.0);
+            draw_text(
+                &status,
+                screen_width() - status_dimensions.width - 40.0,
+                y,
+                25.0,
+                color,
+            );
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use super::{centered, dim_background, Context, Gameplay, MainMenu};
+use crate::enemy::EnemyKind;
+use crate::modes::ModeKind;
+use crate::replay::Replay;
+use crate::scene::{Scene, Transition};
+use crate::stats::RunStats;
+use crate::versus::RoundResult;
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+/// Key that starts a new run straight from the results screen.
+const RETRY_KEY: KeyCode = KeyCode::R;
+
+/// How a finished run went.
+pub struct Summary {
+    pub mode: ModeKind,
+    /// Whether the game mode ended the run rather than the ship dying.
+    pub finished: bool,
+    pub score: u32,
+    /// Each player's own score, in player order.
+    pub player_scores: Vec<u32>,
+    pub best_chain: u32,
+    pub stats: RunStats,
+    /// Zero-based leaderboard position, if the run was ranked and placed.
+    pub rank: Option<usize>,
+    pub new_high_score: bool,
+    /// How the round went, for a versus run.
+    pub versus: Option<RoundResult>,
+}
+
+/// Results overlay shown over the final frame of a run.
+pub struct GameOver {
+    summary: Summary,
+    replay: Replay,
+}
+
+impl GameOver {
+    pub fn new(summary: Summary, replay: Replay) -> Self {
+        GameOver { summary, replay }
+    }
+
+    fn lines(&self) -> (Vec<String>, Vec<String>) {
+        let summary = &self.summary;
+        let stats = &summary.stats;
+        let seconds = stats.time_survived as u32;
+        let rank = match summary.rank {
+            Some(rank) => format!("Rank #{}", rank + 1),
+            None => "Unranked".to_string(),
+        };
+        if let Some(result) = &summary.versus {
+            return self.versus_lines(result);
+        }
+        let mut left = vec![
+            format!("Score {}", summary.score),
+            format!("Time {}:{:02}", seconds / 60, seconds % 60),
+            format!("Best chain {}", summary.best_chain),
+            rank,
+        ];
+        if summary.player_scores.len() > 1 {
+            let scores: Vec<_> = summary
+                .player_scores
+                .iter()
+                .enumerate()
+                .map(|(index, score)| format!("P{} {}", index + 1, score))
+                .collect();
+            left.push(scores.join(" / "));
+        }
+        let right = vec![
+            format!(
+                "Kills {} / {} / {}",
+                stats.kills_of(EnemyKind::Small),
+                stats.kills_of(EnemyKind::Medium),
+                stats.kills_of(EnemyKind::Big)
+            ),
+            format!("Shots {}", stats.shots_fired),
+            format!("Accuracy {:.0}%", stats.accuracy() * 100.0),
+            summary.mode.rules().name().to_string(),
+        ];
+        (left, right)
+    }
+
+    fn versus_lines(&self, result: &RoundResult) -> (Vec<String>, Vec<String>) {
+        let summary = &self.summary;
+        let stats = &summary.stats;
+        let seconds = stats.time_survived as u32;
+        let standings = &result.standings;
+        let mut left: Vec<_> = summary
+            .player_scores
+            .iter()
+            .enumerate()
+            .map(|(index, score)| format!("P{} score {}", index + 1, score))
+            .collect();
+        left.push(format!("Time {}:{:02}", seconds / 60, seconds % 60));
+        left.push(format!(
+            "Match {} - {}",
+            standings.wins[0], standings.wins[1]
+        ));
+        let right = vec![
+            format!(
+                "Kills {} / {} / {}",
+                stats.kills_of(EnemyKind::Small),
+                stats.kills_of(EnemyKind::Medium),
+                stats.kills_of(EnemyKind::Big)
+            ),
+            format!("Shots {}", stats.shots_fired),
+            format!("Accuracy {:.0}%", stats.accuracy() * 100.0),
+            format!("Versus, best of {}", standings.best_of),
+        ];
+        (left, right)
+    }
+
+    fn title(&self) -> (String, f32) {
+        let summary = &self.summary;
+        if let Some(result) = &summary.versus {
+            return match (result.standings.winner(), result.winner) {
+                (Some(winner), _) => (format!("P{} Wins the Match!", winner + 1), 170.0),
+                (None, Some(winner)) => (format!("P{} Wins the Round!", winner + 1), 170.0),
+                (None, None) => ("Draw!".to_string(), 315.0),
+            };
+        }
+        let (title, title_x) = if summary.new_high_score {
+            ("New High Score!", 200.0)
+        } else if summary.finished {
+            ("Time's Up!", 265.0)
+        } else {
+            ("Game Over!", 265.0)
+        };
+        (title.to_string(), title_x)
+    }
+}
+
+impl Scene for GameOver {
+    fn update(&mut self, ctx: &mut Context, _delta: f32) -> Transition {
+        if is_key_pressed(RETRY_KEY) {
+            return Transition::Switch(Box::new(Gameplay::new(ctx)));
+        }
+
+        let window_size = vec2(740.0, 520.0);
+        let (title, title_x) = self.title();
+        // Retry plays the next round while a versus match is undecided.
+        let retry_label = match &self.summary.versus {
+            Some(result) if result.standings.winner().is_none() => "Next",
+            _ => "Retry",
+        };
+        let (left, right) = self.lines();
+        // The buttons sit below however many rows there are.
+        let top = 35.0 + left.len().max(right.len()) as f32 * 35.0;
+        let mut transition = Transition::None;
+        root_ui().window(hash!(), centered(window_size), window_size, |ui| {
+            ui.label(vec2(title_x, -34.0), &title);
+            for (row, line) in left.iter().enumerate() {
+                ui.label(vec2(40.0, 10.0 + row as f32 * 35.0), line);
+            }
+            for (row, line) in right.iter().enumerate() {
+                ui.label(vec2(380.0, 10.0 + row as f32 * 35.0), line);
+            }
+            if ui.button(vec2(40.0, top), retry_label) {
+                transition = Transition::Switch(Box::new(Gameplay::new(ctx)));
+            }
+            if ui.button(vec2(300.0, top), "Same seed") {
+                transition =
+                    Transition::Switch(Box::new(Gameplay::with_seed(ctx, self.replay.seed)));
+            }
+            if ui.button(vec2(40.0, top + 100.0), "Replay") {
+                transition = Transition::Push(Box::new(Gameplay::replay(ctx, self.replay.clone())));
+            }
+            if ui.button(vec2(300.0, top + 100.0), "Main Menu") {
+                transition = Transition::Switch(Box::new(MainMenu::new()));
+            }
+            ui.label(vec2(200.0, top + 210.0), "Press R to retry");
+        });
+        transition
+    }
+
+    fn draw(&mut self, _ctx: &Context) {
+        dim_background();
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
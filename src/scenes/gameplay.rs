@@ -0,0 +1,876 @@
+use super::{modifier_names, Context, GameOver, Pause, Players, Summary, LEADERBOARD_PATH};
+use crate::achievements::ACHIEVEMENTS_PATH;
+use crate::bot::Bot;
+use crate::components::Health;
+use crate::daily::{self, DAILY_PATH};
+use crate::ecs::Entity;
+use crate::effects::{Explosions, SoundEffects};
+use crate::enemy::EnemyKind;
+use crate::events::Event;
+use crate::leaderboard::Entry;
+use crate::lifetime::{append_history, RunEnd, RunRecord, HISTORY_PATH, LIFETIME_PATH};
+use crate::modes::ModeKind;
+use crate::pool::Pool;
+use crate::replay::{Frame, Replay, REPLAY_PATH};
+use crate::resources::{Resources, SpriteSheet};
+use crate::scene::{Scene, Transition};
+use crate::versus::{RoundResult, ROUND_SECONDS};
+use crate::world::{Controller, Input, SimParams, World, MAX_PLAYERS};
+use macroquad::experimental::animation::AnimatedSprite;
+use macroquad::experimental::collections::storage;
+use macroquad::prelude::*;
+use macroquad_particles::{self as particles, AtlasConfig, Emitter};
+
+/// Explosion particles, each a random frame of `sheet`.
+fn particle_explosion(sheet: &SpriteSheet) -> particles::EmitterConfig {
+    particles::EmitterConfig {
+        local_coords: false,
+        one_shot: true,
+        emitting: true,
+        lifetime: 0.6,
+        lifetime_randomness: 0.3,
+        explosiveness: 0.65,
+        initial_direction_spread: 2.0 * std::f32::consts::PI,
+        initial_velocity: 400.0,
+        initial_velocity_randomness: 0.8,
+        size: 16.0,
+        size_randomness: 0.3,
+        texture: Some(sheet.texture.clone()),
+        atlas: Some(AtlasConfig::new(
+            sheet.columns() as u16,
+            sheet.rows() as u16,
+            0..,
+        )),
+        ..Default::default()
+    }
+}
+
+/// Hit points as a row of squares from (`x`, `y`).
+fn draw_health(health: Health, x: f32, y: f32) {
+    for life in 0..health.max_hp {
+        let color = if life < health.hp { RED } else { DARKGRAY };
+        draw_rectangle(x + life as f32 * 20.0, y, 14.0, 14.0, color);
+    }
+}
+
+/// The arena `replay` is played in: the daily's own, or else the window.
+fn arena(replay: &Replay) -> Vec2 {
+    match replay.daily {
+        Some(_) => daily::ARENA,
+        None => vec2(screen_width(), screen_height()),
+    }
+}
+
+/// How large `arena` is drawn: as large as it fits in the window without
+/// stretching. An arena the size of the window is drawn one to one.
+fn arena_scale(arena: Vec2) -> f32 {
+    (screen_width() / arena.x).min(screen_height() / arena.y)
+}
+
+/// Draws `arena` centred in the window, at [`arena_scale`].
+fn arena_camera(arena: Vec2) -> Camera2D {
+    let scale = arena_scale(arena);
+    Camera2D {
+        target: arena / 2.0,
+        zoom: vec2(2.0 * scale / screen_width(), 2.0 * scale / screen_height()),
+        ..Default::default()
+    }
+}
+
+/// Covers everything further than `radius` from `center`, fading in over the
+/// last quarter of the radius, out past the window's edges around `arena`.
+fn draw_fog(center: Vec2, radius: f32, arena: Vec2) {
+    const SIDES: usize = 64;
+    let far = screen_width().hypot(screen_height()) / arena_scale(arena) * 2.0;
+    let edge = radius * 0.75;
+    let ring = |inner: f32, outer: f32, color: Color| {
+        for side in 0..SIDES {
+            let from = Vec2::from_angle(side as f32 / SIDES as f32 * std::f32::consts::TAU);
+            let to = Vec2::from_angle((side + 1) as f32 / SIDES as f32 * std::f32::consts::TAU);
+            draw_triangle(
+                center + from * inner,
+                center + from * outer,
+                center + to * outer,
+                color,
+            );
+            draw_triangle(
+                center + from * inner,
+                center + to * outer,
+                center + to * inner,
+                color,
+            );
+        }
+    };
+    ring(edge, radius, Color::new(0.0, 0.0, 0.0, 0.6));
+    ring(radius, far, BLACK);
+}
+
+/// Keys one player flies with.
+#[derive(Clone, Copy)]
+struct KeyLayout {
+    left: KeyCode,
+    right: KeyCode,
+    up: KeyCode,
+    down: KeyCode,
+    fire: KeyCode,
+}
+
+impl Controller for KeyLayout {
+    fn input(&mut self, _world: &World, _ship: Entity) -> Input {
+        let mut input = Input::default();
+        if is_key_down(self.left) {
+            input.movement.x -= 1.0;
+        }
+        if is_key_down(self.right) {
+            input.movement.x += 1.0;
+        }
+        if is_key_down(self.up) {
+            input.movement.y -= 1.0;
+        }
+        if is_key_down(self.down) {
+            input.movement.y += 1.0;
+        }
+        input.fire = is_key_down(self.fire);
+        input
+    }
+}
+
+/// Player one flies with the arrows and Space, player two with WASD and left
+/// Shift. macroquad has no gamepad input, so both share the keyboard.
+const KEY_LAYOUTS: [KeyLayout; MAX_PLAYERS] = [
+    KeyLayout {
+        left: KeyCode::Left,
+        right: KeyCode::Right,
+        up: KeyCode::Up,
+        down: KeyCode::Down,
+        fire: KeyCode::Space,
+    },
+    KeyLayout {
+        left: KeyCode::A,
+        right: KeyCode::D,
+        up: KeyCode::W,
+        down: KeyCode::S,
+        fire: KeyCode::LeftShift,
+    },
+];
+
+/// Seconds a finished replay stays on screen before returning.
+const REPLAY_LINGER: f32 = 1.5;
+
+/// Most time the daily catches up on in one frame, so that a stall isn't
+/// followed by a burst of steps.
+const MAX_CATCH_UP: f32 = 0.25;
+
+enum Mode {
+    /// Played from the keyboard and recorded.
+    Live,
+    /// Played back from a recording.
+    Playback { cursor: usize, linger: f32 },
+    /// Flown by the bot while the main menu is idle, silently and without
+    /// recording anything.
+    Attract,
+}
+
+/// A run in progress, or the replay of one.
+pub struct Gameplay {
+    world: World,
+    mode: Mode,
+    replay: Replay,
+    /// Whatever flies each ship in a live or attract run, by player index.
+    controllers: Vec<Box<dyn Controller>>,
+    explosions: Explosions<Emitter>,
+    /// Time the daily's fixed step hasn't caught up with yet.
+    accumulator: f32,
+    run_ranked: bool,
+    /// Whether adaptive difficulty scales the run.
+    adaptive: bool,
+    /// Leaderboard table the run is ranked on.
+    table: String,
+    /// Whether the score has gone to the leaderboard yet.
+    recorded: bool,
+    rank: Option<usize>,
+    high_score: u32,
+    old_high_score: u32,
+    direction_modifier: f32,
+    /// One per ship, by player index.
+    ship_sprites: Vec<AnimatedSprite>,
+    bullet_sprite: AnimatedSprite,
+    enemy_small_sprite: AnimatedSprite,
+    enemy_medium_sprite: AnimatedSprite,
+    enemy_big_sprite: AnimatedSprite,
+}
+
+impl Gameplay {
+    pub fn new(ctx: &Context) -> Self {
+        Gameplay::with_seed(ctx, miniquad::date::now() as u64)
+    }
+
+    /// A new run with the current settings whose enemies come exactly as in
+    /// every other run with this seed, as long as the inputs match. The daily
+    /// challenge, if picked, brings its own seed, is always played alone as
+    /// Classic without mutators and only the first run of the day is ranked.
+    /// Versus rounds are timed Classic runs and never ranked.
+    pub fn with_seed(ctx: &Context, seed: u64) -> Self {
+        let replay = match &ctx.daily {
+            Some(daily) => Replay {
+                daily: Some(daily.clone()),
+                ..Replay::new(daily.seed, daily.difficulty(), ctx.tuning)
+            },
+            None => Replay {
+                mode: match ctx.players {
+                    Players::Versus => ModeKind::Classic,
+                    _ => ctx.mode,
+                },
+                mutators: ctx.mutators,
+                coop: (ctx.players == Players::Coop).then_some(ctx.coop),
+                versus: (ctx.players == Players::Versus).then_some(ctx.versus),
+                ..Replay::new(seed, ctx.preset.difficulty(), ctx.tuning)
+            },
+        };
+        let mut gameplay = Gameplay::build(ctx, replay, Mode::Live);
+        match &ctx.daily {
+            Some(daily) => gameplay.run_ranked = !ctx.daily_attempts.attempted(daily.date),
+            None => {
+                gameplay.adaptive = ctx.adaptive_enabled;
+                gameplay.run_ranked = !ctx.adaptive_enabled && gameplay.replay.versus.is_none();
+            }
+        }
+        gameplay
+    }
+
+    /// A demo run flown by the bot, for attract mode. Any key or click ends it.
+    pub fn attract(ctx: &Context) -> Self {
+        let replay = Replay::new(
+            miniquad::date::now() as u64,
+            ctx.preset.difficulty(),
+            ctx.tuning,
+        );
+        let mut gameplay = Gameplay::build(ctx, replay, Mode::Attract);
+        gameplay.controllers = vec![Box::new(Bot::default())];
+        gameplay.recorded = true;
+        gameplay
+    }
+
+    /// Plays `replay` back. Nothing it does is scored.
+    pub fn replay(ctx: &Context, replay: Replay) -> Self {
+        let mut gameplay = Gameplay::build(
+            ctx,
+            replay,
+            Mode::Playback {
+                cursor: 0,
+                linger: REPLAY_LINGER,
+            },
+        );
+        gameplay.recorded = true;
+        gameplay
+    }
+
+    fn build(ctx: &Context, replay: Replay, mode: Mode) -> Self {
+        let resources = storage::get::<Resources>();
+        let mut params = SimParams::default();
+        let tuning = replay.tuning;
+        tuning.apply(&mut params);
+        replay.mutators.apply(&mut params);
+        params.coop = replay.coop;
+        params.versus = replay.versus;
+        let table = match &replay.daily {
+            Some(daily) => {
+                daily.apply(&mut params);
+                daily.table()
+            }
+            None if replay.coop.is_some() => {
+                format!(
+                    "{}.coop",
+                    replay.mode.rules().table(replay.difficulty.preset)
+                )
+            }
+            None if replay.versus.is_some() => {
+                format!("versus.{}", replay.difficulty.preset.key())
+            }
+            None => replay.mode.rules().table(replay.difficulty.preset),
+        };
+        let mut world = World::new(
+            arena(&replay),
+            replay.difficulty,
+            ctx.pool_caps,
+            replay.seed,
+            params,
+        );
+        world.mode = replay.mode;
+        let high_score = ctx.leaderboard.best(&table);
+        let mut bullet_sprite = resources.bullet.animated_sprite();
+        bullet_sprite.set_animation(1);
+
+        Gameplay {
+            world,
+            mode,
+            replay,
+            controllers: KEY_LAYOUTS
+                .iter()
+                .map(|&keys| Box::new(keys) as Box<dyn Controller>)
+                .collect(),
+            explosions: Explosions {
+                pool: Pool::new(ctx.pool_caps.explosions),
+                config: particle_explosion(&resources.explosion),
+                particles: tuning.effects.explosion_particles,
+            },
+            accumulator: 0.0,
+            run_ranked: false,
+            adaptive: false,
+            table,
+            recorded: false,
+            rank: None,
+            high_score,
+            old_high_score: high_score,
+            direction_modifier: 0.0,
+            ship_sprites: (0..MAX_PLAYERS)
+                .map(|_| resources.ship.animated_sprite())
+                .collect(),
+            bullet_sprite,
+            enemy_small_sprite: resources.enemy_small.animated_sprite(),
+            enemy_medium_sprite: resources.enemy_medium.animated_sprite(),
+            enemy_big_sprite: resources.enemy_big.animated_sprite(),
+        }
+    }
+
+    /// The live frame for this step: the controllers' input plus whatever
+    /// adaptive difficulty wants, recorded for the replay of a live run.
+    fn live_frame(&mut self, ctx: &mut Context, delta: f32) -> Frame {
+        let (spawn_scale, speed_scale) = if let Some(daily) = &self.replay.daily {
+            (daily.spawn_scale(), daily.speed_scale())
+        } else if !self.adaptive {
+            (1.0, 1.0)
+        } else {
+            if let Some(adjustment) = ctx
+                .adaptive
+                .update(delta, get_time(), &mut self.world.stats)
+            {
+                debug!(
+                    "adaptive difficulty at {:.0}s: level {:.2}, spawn x{:.2}, speed x{:.2}",
+                    adjustment.time,
+                    adjustment.level,
+                    adjustment.spawn_scale,
+                    adjustment.speed_scale
+                );
+            }
+            (ctx.adaptive.spawn_scale(), ctx.adaptive.speed_scale())
+        };
+
+        let mut inputs = [Input::default(); MAX_PLAYERS];
+        for ((input, controller), &ship) in inputs
+            .iter_mut()
+            .zip(&mut self.controllers)
+            .zip(&self.world.ships)
+        {
+            *input = controller.input(&self.world, ship);
+        }
+
+        let frame = Frame {
+            inputs,
+            delta,
+            arena: arena(&self.replay),
+            spawn_scale,
+            speed_scale,
+        };
+        if matches!(self.mode, Mode::Live) {
+            self.replay.record(frame);
+        }
+        frame
+    }
+
+    /// Steps the world through `frame` and hands its events out. Returns how
+    /// the run ended, if this step ended it.
+    fn advance(&mut self, ctx: &mut Context, frame: Frame) -> Option<RunEnd> {
+        self.direction_modifier -= frame.inputs[0].movement.x * 0.05 * frame.delta;
+
+        let world = &mut self.world;
+        let resources = storage::get::<Resources>();
+        let events = frame.apply(world);
+        events.dispatch(&mut self.explosions);
+        if !matches!(self.mode, Mode::Attract) {
+            events.dispatch(&mut SoundEffects {
+                play: |sfx| ctx.play_sfx(resources.sfx(sfx)),
+            });
+            events.dispatch(&mut ctx.achievements);
+            ctx.achievements.update(frame.delta);
+            for achievement in ctx.achievements.take_unlocked() {
+                ctx.toasts.push(achievement.name, achievement.description);
+            }
+        }
+        let mut ended = None;
+        for event in events.iter() {
+            match *event {
+                Event::PlayerDied { by, .. } => ended = Some(RunEnd::Died(by)),
+                Event::RunFinished => ended = Some(RunEnd::Finished),
+                _ => {}
+            }
+        }
+        if self.run_ranked {
+            self.high_score = self.high_score.max(world.score);
+        }
+        ended
+    }
+
+    /// Submits the score of a ranked run and folds it into the lifetime
+    /// statistics and history, once, however the run ends.
+    fn end_run(&mut self, ctx: &mut Context, end: RunEnd) {
+        if self.recorded {
+            return;
+        }
+        self.recorded = true;
+        if self.run_ranked && self.world.score > 0 {
+            self.rank = ctx.leaderboard.submit(
+                &self.table,
+                Entry {
+                    score: self.world.score,
+                    mutators: self.replay.mutators,
+                },
+            );
+            ctx.leaderboard.save(LEADERBOARD_PATH).ok();
+        }
+        let run = RunRecord {
+            table: &self.table,
+            seed: self.world.seed,
+            score: self.world.score,
+            best_chain: self.world.best_chain(),
+            stats: &self.world.stats,
+            end,
+        };
+        ctx.lifetime.record(&run);
+        ctx.lifetime.save(LIFETIME_PATH).ok();
+        ctx.achievements.end_run();
+        ctx.achievements.save(ACHIEVEMENTS_PATH).ok();
+        append_history(HISTORY_PATH, &run).ok();
+        if matches!(self.mode, Mode::Live) {
+            self.replay.save(REPLAY_PATH).ok();
+            ctx.last_replay = Some(self.replay.clone());
+        }
+    }
+
+    /// Ends the run, which the ships either died in or finished.
+    fn game_over(&mut self, ctx: &mut Context, end: RunEnd) -> Transition {
+        if let RunEnd::Died(_) = end {
+            ctx.adaptive.record_death(get_time());
+        }
+        self.end_run(ctx, end);
+        let player_scores: Vec<u32> = self
+            .world
+            .ships
+            .iter()
+            .filter_map(|&ship| self.world.players.get(ship))
+            .map(|player| player.score)
+            .collect();
+        let versus = self.replay.versus.map(|_| RoundResult {
+            winner: ctx.versus_match.record(&player_scores),
+            standings: ctx.versus_match,
+        });
+        let summary = Summary {
+            mode: self.world.mode,
+            finished: end == RunEnd::Finished,
+            player_scores,
+            versus,
+            score: self.world.score,
+            best_chain: self.world.best_chain(),
+            stats: self.world.stats.clone(),
+            rank: self.rank,
+            new_high_score: self.old_high_score < self.high_score,
+        };
+        Transition::Push(Box::new(GameOver::new(summary, self.replay.clone())))
+    }
+}
+
+impl Gameplay {
+    /// Each player's own score, one line per player from `y` down.
+    fn draw_player_scores(&self, y: f32) {
+        let world = &self.world;
+        for (index, &ship) in world.ships.iter().enumerate() {
+            let score = world.players.get(ship).map_or(0, |player| player.score);
+            draw_text(
+                format!("P{}: {}", index + 1, score),
+                10.0,
+                y + 15.0 + index as f32 * 22.0,
+                22.0,
+                GRAY,
+            );
+        }
+    }
+}
+
+impl Scene for Gameplay {
+    fn on_enter(&mut self, ctx: &mut Context) {
+        if matches!(self.mode, Mode::Live) {
+            ctx.adaptive.start_run();
+            ctx.lifetime.start_run();
+            ctx.achievements.start_run(self.high_score);
+            if let (Some(daily), true) = (&self.replay.daily, self.run_ranked) {
+                ctx.daily_attempts.record(daily.date);
+                ctx.daily_attempts.save(DAILY_PATH).ok();
+            }
+            if self.replay.versus.is_some() && ctx.versus_match.winner().is_some() {
+                ctx.versus_match = ctx.versus_match.restarted();
+            }
+        }
+    }
+
+    fn on_exit(&mut self, ctx: &mut Context) {
+        self.end_run(ctx, RunEnd::Abandoned);
+    }
+
+    fn update(&mut self, ctx: &mut Context, delta: f32) -> Transition {
+        let mut ended = None;
+        if let Mode::Playback { cursor, linger } = &mut self.mode {
+            if is_key_pressed(KeyCode::Escape) {
+                return Transition::Pop;
+            }
+            let Some(&frame) = self.replay.frames.get(*cursor) else {
+                *linger -= delta;
+                if *linger <= 0.0 {
+                    return Transition::Pop;
+                }
+                self.explosions
+                    .pool
+                    .retain(|(explosion, _)| explosion.config.emitting);
+                return Transition::None;
+            };
+            *cursor += 1;
+            ended = self.advance(ctx, frame);
+        } else {
+            if matches!(self.mode, Mode::Attract) {
+                let interrupted = get_last_key_pressed().is_some()
+                    || is_mouse_button_pressed(MouseButton::Left)
+                    || mouse_delta_position() != Vec2::ZERO;
+                if interrupted || self.world.game_over {
+                    return Transition::Pop;
+                }
+            }
+            // The daily takes as many fixed steps as the time passed calls
+            // for, so it plays out the same at any frame rate.
+            let (steps, step) = if self.replay.daily.is_some() {
+                self.accumulator = (self.accumulator + delta).min(MAX_CATCH_UP);
+                let steps = (self.accumulator / daily::DELTA) as u32;
+                self.accumulator -= steps as f32 * daily::DELTA;
+                (steps, daily::DELTA)
+            } else {
+                (1, delta)
+            };
+            for _ in 0..steps {
+                let frame = self.live_frame(ctx, step);
+                ended = self.advance(ctx, frame);
+                if self.world.game_over {
+                    break;
+                }
+            }
+        }
+
+        let world = &self.world;
+        for (sprite, &ship) in self.ship_sprites.iter_mut().zip(&world.ships) {
+            let steer = world
+                .players
+                .get(ship)
+                .map_or(0.0, |player| player.movement.x);
+            if steer < 0.0 {
+                sprite.set_animation(1);
+            } else if steer > 0.0 {
+                sprite.set_animation(2);
+            }
+            sprite.update();
+        }
+        self.bullet_sprite.update();
+        self.enemy_small_sprite.update();
+        self.enemy_medium_sprite.update();
+        self.enemy_big_sprite.update();
+        self.explosions
+            .pool
+            .retain(|(explosion, _)| explosion.config.emitting);
+
+        if !matches!(self.mode, Mode::Live) {
+            return Transition::None;
+        }
+        if let (Some(end), true) = (ended, self.world.game_over) {
+            return self.game_over(ctx, end);
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            return Transition::Push(Box::new(Pause));
+        }
+        Transition::None
+    }
+
+    fn on_focus_lost(&mut self, _ctx: &mut Context) -> Transition {
+        match self.mode {
+            Mode::Live => Transition::Push(Box::new(Pause)),
+            Mode::Playback { .. } | Mode::Attract => Transition::None,
+        }
+    }
+
+    fn draw(&mut self, ctx: &Context) {
+        let resources = storage::get::<Resources>();
+        let world = &self.world;
+        clear_background(BLACK);
+
+        let material = &resources.starfield_material;
+        material.set_uniform("iResolution", (screen_width(), screen_height()));
+        material.set_uniform("direction_modifier", self.direction_modifier);
+        gl_use_material(material);
+        draw_texture_ex(
+            &resources.starfield_target.texture,
+            0.,
+            0.,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(screen_width(), screen_height())),
+                ..Default::default()
+            },
+        );
+        gl_use_default_material();
+
+        set_camera(&arena_camera(world.arena));
+        let ship_textures = [&resources.ship.texture, &resources.second_ship_texture];
+        for (index, &ship) in world.ships.iter().enumerate() {
+            let health = world.ship_health(ship);
+            // Blink while invulnerable after a hit.
+            let blink =
+                health.invulnerable > 0.0 && !((get_time() * 10.0) as u32).is_multiple_of(2);
+            if health.dead() || blink {
+                continue;
+            }
+            let pos = world.ship_pos(ship);
+            let ship_frame = self.ship_sprites[index].frame();
+            draw_texture_ex(
+                ship_textures[index],
+                pos.x - ship_frame.dest_size.x,
+                pos.y - ship_frame.dest_size.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(ship_frame.dest_size * 2.0),
+                    source: Some(ship_frame.source_rect),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let enemy_small_frame = self.enemy_small_sprite.frame();
+        let enemy_medium_frame = self.enemy_medium_sprite.frame();
+        let enemy_big_frame = self.enemy_big_sprite.frame();
+        for (entity, enemy) in world.enemies.iter() {
+            let (Some(transform), Some(body)) =
+                (world.transforms.get(entity), world.bodies.get(entity))
+            else {
+                continue;
+            };
+            let frame = match enemy.kind {
+                EnemyKind::Small => &enemy_small_frame,
+                EnemyKind::Medium => &enemy_medium_frame,
+                EnemyKind::Big => &enemy_big_frame,
+            };
+            draw_texture_ex(
+                &resources.enemy(enemy.kind).texture,
+                transform.pos.x - body.size / 2.0,
+                transform.pos.y - body.size / 2.0,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(body.size, body.size)),
+                    source: Some(frame.source_rect),
+                    ..Default::default()
+                },
+            );
+        }
+        for (explosion, coords) in self.explosions.pool.values_mut() {
+            explosion.draw(*coords);
+        }
+
+        let bullet_frame = self.bullet_sprite.frame();
+        for (entity, _) in world.projectiles.iter() {
+            let (Some(transform), Some(body)) =
+                (world.transforms.get(entity), world.bodies.get(entity))
+            else {
+                continue;
+            };
+            draw_texture_ex(
+                &resources.bullet.texture,
+                transform.pos.x - body.size / 2.0,
+                transform.pos.y - body.size / 2.0,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(vec2(body.size, body.size)),
+                    source: Some(bullet_frame.source_rect),
+                    ..Default::default()
+                },
+            );
+        }
+
+        if let Some(radius) = world.params.vision_radius {
+            // One clearing around every ship still flying, widened to take
+            // them all in.
+            let alive: Vec<Vec2> = world
+                .ships
+                .iter()
+                .filter(|&&ship| world.ship_alive(ship))
+                .map(|&ship| world.ship_pos(ship))
+                .collect();
+            if !alive.is_empty() {
+                let center = alive.iter().sum::<Vec2>() / alive.len() as f32;
+                let spread = alive
+                    .iter()
+                    .map(|pos| pos.distance(center))
+                    .fold(0.0, f32::max);
+                draw_fog(center, radius + spread, world.arena);
+            }
+        }
+
+        set_default_camera();
+
+        // In versus only the players' own scores count.
+        if world.params.versus.is_none() {
+            draw_text(
+                format!("Score: {}", world.score).as_str(),
+                10.0,
+                35.0,
+                25.0,
+                WHITE,
+            );
+        }
+
+        // In a two-player run each player's combo sits side by side.
+        for (index, combo) in world.combos.iter().take(world.ships.len()).enumerate() {
+            if combo.chain() == 0 {
+                continue;
+            }
+            let label = if world.ships.len() == 1 {
+                "Combo".to_string()
+            } else {
+                format!("P{}", index + 1)
+            };
+            let x = 10.0 + index as f32 * 240.0;
+            draw_text(
+                format!(
+                    "{} x{}  ({} chain)",
+                    label,
+                    combo.multiplier(),
+                    combo.chain()
+                )
+                .as_str(),
+                x,
+                60.0,
+                25.0,
+                YELLOW,
+            );
+            draw_rectangle(x, 68.0, 150.0, 6.0, DARKGRAY);
+            draw_rectangle(x, 68.0, 150.0 * combo.meter(), 6.0, YELLOW);
+        }
+
+        if world.ships.len() == 1 {
+            draw_health(world.ship_health(world.ships[0]), 10.0, 84.0);
+        } else if world.params.coop.is_some_and(|coop| coop.shared_lives) {
+            draw_text("Team", 10.0, 97.0, 20.0, WHITE);
+            draw_health(world.ship_health(world.ships[0]), 60.0, 84.0);
+            self.draw_player_scores(110.0);
+        } else if world.params.versus.is_some() {
+            // Versus ships never go down, so there is no health to show.
+            self.draw_player_scores(84.0);
+        } else {
+            for (index, &ship) in world.ships.iter().enumerate() {
+                let y = 84.0 + index as f32 * 20.0;
+                draw_text(format!("P{}", index + 1), 10.0, y + 13.0, 20.0, WHITE);
+                draw_health(world.ship_health(ship), 40.0, y);
+            }
+            self.draw_player_scores(84.0 + world.ships.len() as f32 * 20.0 + 6.0);
+        }
+
+        if let Mode::Playback { .. } | Mode::Attract = self.mode {
+            let replay_text = match self.mode {
+                Mode::Attract => "Demo - press any key",
+                _ => "Replay - Escape to skip",
+            };
+            let text_dimensions = measure_text(replay_text, None, 25, 1.0);
+            draw_text(
+                replay_text,
+                screen_width() - text_dimensions.width - 10.0,
+                60.0,
+                25.0,
+                GRAY,
+            );
+        } else if let Some(daily) = &self.replay.daily {
+            let daily_text = if self.run_ranked {
+                format!("Daily {}", daily.date)
+            } else {
+                format!("Daily {} (practice)", daily.date)
+            };
+            let text_dimensions = measure_text(daily_text.as_str(), None, 25, 1.0);
+            draw_text(
+                daily_text.as_str(),
+                screen_width() - text_dimensions.width - 10.0,
+                60.0,
+                25.0,
+                GRAY,
+            );
+            let modifiers_text = modifier_names(daily);
+            let text_dimensions = measure_text(modifiers_text.as_str(), None, 25, 1.0);
+            draw_text(
+                modifiers_text.as_str(),
+                screen_width() - text_dimensions.width - 10.0,
+                85.0,
+                25.0,
+                GRAY,
+            );
+        } else if self.adaptive {
+            let adaptive_text = format!("Adaptive x{:.2} (unranked)", world.spawn_scale);
+            let text_dimensions = measure_text(adaptive_text.as_str(), None, 25, 1.0);
+            draw_text(
+                adaptive_text.as_str(),
+                screen_width() - text_dimensions.width - 10.0,
+                60.0,
+                25.0,
+                GRAY,
+            );
+        }
+
+        if !self.replay.mutators.is_empty() {
+            draw_text(
+                self.replay.mutators.names().as_str(),
+                10.0,
+                screen_height() - 15.0,
+                25.0,
+                GRAY,
+            );
+        }
+
+        let status = match world.params.versus {
+            Some(_) => {
+                let standings = &ctx.versus_match;
+                let left = (ROUND_SECONDS - world.stats.time_survived).max(0.0).ceil() as u32;
+                Some(format!(
+                    "Round {}  {} - {}  {}:{:02}",
+                    standings.rounds + 1,
+                    standings.wins[0],
+                    standings.wins[1],
+                    left / 60,
+                    left % 60
+                ))
+            }
+            None => world.mode.rules().status(world),
+        };
+        if let Some(status) = status {
+            let text_dimensions = measure_text(status.as_str(), None, 25, 1.0);
+            draw_text(
+                status.as_str(),
+                screen_width() / 2.0 - text_dimensions.width / 2.0,
+                35.0,
+                25.0,
+                YELLOW,
+            );
+        }
+
+        if world.params.versus.is_some() {
+            return;
+        }
+        let highscore_text = format!("High score: {}", self.high_score);
+        let text_dimensions = measure_text(highscore_text.as_str(), None, 25, 1.0);
+        draw_text(
+            highscore_text.as_str(),
+            screen_width() - text_dimensions.width - 10.0,
+            35.0,
+            25.0,
+            WHITE,
+        );
+    }
+}
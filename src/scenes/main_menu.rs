@@ -0,0 +1,93 @@
+use super::{
+    centered, modifier_names, Confirm, ConfirmAction, Context, Gameplay, MutatorMenu, Options,
+    PlayersMenu, Statistics,
+};
+use crate::daily::DailyChallenge;
+use crate::scene::{Scene, Transition};
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+/// Seconds without input before the menu starts a bot-flown demo run.
+const ATTRACT_DELAY: f32 = 20.0;
+
+#[derive(Default)]
+pub struct MainMenu {
+    /// Seconds since the last key press, click or mouse movement.
+    idle: f32,
+}
+
+impl MainMenu {
+    pub fn new() -> Self {
+        MainMenu::default()
+    }
+}
+
+impl Scene for MainMenu {
+    fn update(&mut self, ctx: &mut Context, delta: f32) -> Transition {
+        let active = get_last_key_pressed().is_some()
+            || is_mouse_button_down(MouseButton::Left)
+            || mouse_delta_position() != Vec2::ZERO;
+        self.idle = if active { 0.0 } else { self.idle + delta };
+        if self.idle >= ATTRACT_DELAY {
+            self.idle = 0.0;
+            return Transition::Push(Box::new(Gameplay::attract(ctx)));
+        }
+
+        let window_size = vec2(780.0, 560.0);
+        let mut transition = Transition::None;
+        root_ui().window(hash!(), centered(window_size), window_size, |ui| {
+            ui.label(vec2(285.0, -34.0), "Main Menu");
+            if ui.button(vec2(25.0, 25.0), "Play") {
+                ctx.daily = None;
+                ctx.versus_match = ctx.versus_match.restarted();
+                transition = Transition::Switch(Box::new(Gameplay::new(ctx)));
+            }
+            if ui.button(vec2(25.0, 115.0), ctx.mode.label()) {
+                ctx.mode = ctx.mode.next();
+            }
+            if ui.button(vec2(25.0, 205.0), ctx.preset.name()) {
+                ctx.preset = ctx.preset.next();
+            }
+            let adaptive_label = if ctx.adaptive_enabled {
+                "Adaptive"
+            } else {
+                "Fixed"
+            };
+            if ui.button(vec2(25.0, 295.0), adaptive_label) {
+                ctx.adaptive_enabled = !ctx.adaptive_enabled;
+            }
+            let daily = DailyChallenge::today();
+            let daily_label = if ctx.daily_attempts.attempted(daily.date) {
+                "Practice"
+            } else {
+                "Daily"
+            };
+            if ui.button(vec2(25.0, 385.0), daily_label) {
+                ctx.daily = Some(daily.clone());
+                transition = Transition::Switch(Box::new(Gameplay::new(ctx)));
+            }
+            if ui.button(vec2(420.0, 25.0), "Mutators") {
+                transition = Transition::Push(Box::new(MutatorMenu));
+            }
+            if ui.button(vec2(420.0, 115.0), "Stats") {
+                transition = Transition::Push(Box::new(Statistics));
+            }
+            if ui.button(vec2(420.0, 205.0), "Options") {
+                transition = Transition::Push(Box::new(Options));
+            }
+            if ui.button(vec2(420.0, 295.0), "Quit") {
+                transition = Transition::Push(Box::new(Confirm::new(ConfirmAction::Quit)));
+            }
+            if ui.button(vec2(420.0, 385.0), "Players") {
+                transition = Transition::Push(Box::new(PlayersMenu));
+            }
+            ui.label(vec2(25.0, 475.0), &format!("Daily {}", daily.date));
+            ui.label(vec2(420.0, 475.0), &modifier_names(&daily));
+        });
+        transition
+    }
+
+    fn draw(&mut self, _ctx: &Context) {
+        clear_background(BLACK);
+    }
+}
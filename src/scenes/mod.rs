@@ -0,0 +1,147 @@
+//! The game's scenes and the state they share.
+
+mod confirm;
+mod gallery;
+mod game_over;
+mod gameplay;
+mod main_menu;
+mod mutator_menu;
+mod options;
+mod pause;
+mod players_menu;
+mod statistics;
+mod toasts;
+
+pub use confirm::{Confirm, ConfirmAction};
+pub use gallery::Gallery;
+pub use game_over::{GameOver, Summary};
+pub use gameplay::Gameplay;
+pub use main_menu::MainMenu;
+pub use mutator_menu::MutatorMenu;
+pub use options::Options;
+pub use pause::Pause;
+pub use players_menu::{Players, PlayersMenu};
+pub use statistics::Statistics;
+pub use toasts::Toasts;
+
+use crate::achievements::{Achievements, ACHIEVEMENTS_PATH};
+use crate::adaptive::Adaptive;
+use crate::daily::{DailyAttempts, DailyChallenge, DAILY_PATH};
+use crate::difficulty::Preset;
+use crate::focus::FocusWatcher;
+use crate::leaderboard::Leaderboard;
+use crate::lifetime::{LifetimeStats, LIFETIME_PATH};
+use crate::modes::ModeKind;
+use crate::mutators::Mutators;
+use crate::pool::PoolCaps;
+use crate::replay::{Replay, REPLAY_PATH};
+use crate::resources::Resources;
+use crate::settings::{Settings, SETTINGS_PATH};
+use crate::tuning::Tuning;
+use crate::versus::{Match, Versus};
+use crate::world::Coop;
+use macroquad::audio::{play_sound, set_sound_volume, PlaySoundParams, Sound};
+use macroquad::experimental::collections::storage;
+use macroquad::prelude::*;
+
+pub const LEADERBOARD_PATH: &str = "leaderboard.dat";
+/// The single high score from before leaderboards, kept up to date with the
+/// best Normal score for anything that still reads it.
+pub const LEGACY_HIGH_SCORE_PATH: &str = "highscore.dat";
+
+/// State that outlives any one scene. The [`Resources`] live in macroquad's
+/// storage.
+pub struct Context {
+    pub settings: Settings,
+    pub leaderboard: Leaderboard,
+    pub lifetime: LifetimeStats,
+    pub achievements: Achievements,
+    pub toasts: Toasts,
+    pub preset: Preset,
+    pub mode: ModeKind,
+    pub mutators: Mutators,
+    pub players: Players,
+    pub coop: Coop,
+    pub versus: Versus,
+    /// The versus match in progress, or the last one.
+    pub versus_match: Match,
+    /// The challenge new runs play instead of the preset, after the player
+    /// picked the daily challenge.
+    pub daily: Option<DailyChallenge>,
+    pub daily_attempts: DailyAttempts,
+    pub adaptive: Adaptive,
+    pub adaptive_enabled: bool,
+    pub pool_caps: PoolCaps,
+    pub tuning: Tuning,
+    pub focus: FocusWatcher,
+    /// The last live run, to watch again.
+    pub last_replay: Option<Replay>,
+}
+
+impl Context {
+    /// Writes everything persistent to disk.
+    pub fn save(&self) {
+        self.leaderboard.save(LEADERBOARD_PATH).ok();
+        self.settings.save(SETTINGS_PATH).ok();
+        self.lifetime.save(LIFETIME_PATH).ok();
+        self.achievements.save(ACHIEVEMENTS_PATH).ok();
+        self.daily_attempts.save(DAILY_PATH).ok();
+        if let Some(replay) = &self.last_replay {
+            replay.save(REPLAY_PATH).ok();
+        }
+        let high_score = self.leaderboard.best(Preset::Normal.key());
+        if high_score > 0 {
+            std::fs::write(LEGACY_HIGH_SCORE_PATH, high_score.to_string()).ok();
+        }
+    }
+
+    pub fn apply_music_volume(&self) {
+        let resources = storage::get::<Resources>();
+        if let Some(music) = &resources.theme_music {
+            set_sound_volume(music, self.settings.music_volume);
+        }
+    }
+
+    /// Plays a sound effect, if it loaded.
+    pub fn play_sfx(&self, sound: Option<&Sound>) {
+        let Some(sound) = sound else {
+            return;
+        };
+        play_sound(
+            sound,
+            PlaySoundParams {
+                looped: false,
+                volume: self.settings.sfx_volume,
+            },
+        );
+    }
+}
+
+/// Top-left corner that centres a window of `size` on screen.
+fn centered(size: Vec2) -> Vec2 {
+    vec2(
+        screen_width() / 2.0 - size.x / 2.0,
+        screen_height() / 2.0 - size.y / 2.0,
+    )
+}
+
+/// The daily challenge's modifiers as a comma-separated list.
+fn modifier_names(daily: &DailyChallenge) -> String {
+    daily
+        .modifiers
+        .iter()
+        .map(|modifier| modifier.name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Dims whatever is below an overlay.
+fn dim_background() {
+    draw_rectangle(
+        0.0,
+        0.0,
+        screen_width(),
+        screen_height(),
+        Color::new(0.0, 0.0, 0.0, 0.5),
+    );
+}
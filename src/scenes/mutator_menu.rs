@@ -0,0 +1,51 @@
+use super::{centered, dim_background, Context};
+use crate::mutators::Mutator;
+use crate::scene::{Scene, Transition};
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+/// Toggles the mutators for the next runs.
+pub struct MutatorMenu;
+
+impl Scene for MutatorMenu {
+    fn update(&mut self, ctx: &mut Context, _delta: f32) -> Transition {
+        let window_size = vec2(780.0, 560.0);
+        let mut transition = Transition::None;
+        let active: Vec<_> = ctx.mutators.iter().map(|mutator| mutator.name()).collect();
+        root_ui().window(hash!(), centered(window_size), window_size, |ui| {
+            ui.label(vec2(300.0, -34.0), "Mutators");
+            for (index, mutator) in Mutator::ALL.into_iter().enumerate() {
+                let position = vec2(
+                    25.0 + (index % 2) as f32 * 395.0,
+                    25.0 + (index / 2) as f32 * 90.0,
+                );
+                if ui.button(position, mutator.label()) {
+                    ctx.mutators.toggle(mutator);
+                }
+            }
+            if active.is_empty() {
+                ui.label(vec2(25.0, 300.0), "No mutators");
+            } else {
+                ui.label(vec2(25.0, 300.0), &active[..active.len().min(3)].join(", "));
+                if active.len() > 3 {
+                    ui.label(vec2(25.0, 335.0), &active[3..].join(", "));
+                }
+            }
+            if ui.button(vec2(285.0, 385.0), "Back") {
+                transition = Transition::Pop;
+            }
+        });
+        if is_key_pressed(KeyCode::Escape) {
+            transition = Transition::Pop;
+        }
+        transition
+    }
+
+    fn draw(&mut self, _ctx: &Context) {
+        dim_background();
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
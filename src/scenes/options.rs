@@ -0,0 +1,43 @@
+use super::{centered, dim_background, Context};
+use crate::scene::{Scene, Transition};
+use crate::settings::{next_volume, SETTINGS_PATH};
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+/// Volume settings, saved when the screen is closed.
+pub struct Options;
+
+impl Scene for Options {
+    fn on_exit(&mut self, ctx: &mut Context) {
+        ctx.settings.save(SETTINGS_PATH).ok();
+    }
+
+    fn update(&mut self, ctx: &mut Context, _delta: f32) -> Transition {
+        let window_size = vec2(500.0, 420.0);
+        let mut transition = Transition::None;
+        let music_label = format!("Music {:.0}%", ctx.settings.music_volume * 100.0);
+        let sfx_label = format!("Sound {:.0}%", ctx.settings.sfx_volume * 100.0);
+        root_ui().window(hash!(), centered(window_size), window_size, |ui| {
+            ui.label(vec2(160.0, -34.0), "Options");
+            if ui.button(vec2(55.0, 25.0), music_label.as_str()) {
+                ctx.settings.music_volume = next_volume(ctx.settings.music_volume);
+                ctx.apply_music_volume();
+            }
+            if ui.button(vec2(55.0, 125.0), sfx_label.as_str()) {
+                ctx.settings.sfx_volume = next_volume(ctx.settings.sfx_volume);
+            }
+            if ui.button(vec2(135.0, 225.0), "Back") {
+                transition = Transition::Pop;
+            }
+        });
+        transition
+    }
+
+    fn draw(&mut self, _ctx: &Context) {
+        dim_background();
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,42 @@
+use super::{centered, dim_background, Confirm, ConfirmAction, Context, Options};
+use crate::scene::{Scene, Transition};
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+/// Overlay shown over a frozen run. Escape toggles it; macroquad has no
+/// gamepad input, so there is no Start button binding.
+pub struct Pause;
+
+impl Scene for Pause {
+    fn update(&mut self, _ctx: &mut Context, _delta: f32) -> Transition {
+        let window_size = vec2(500.0, 520.0);
+        let mut transition = Transition::None;
+        root_ui().window(hash!(), centered(window_size), window_size, |ui| {
+            ui.label(vec2(175.0, -34.0), "Paused");
+            if ui.button(vec2(100.0, 25.0), "Resume") {
+                transition = Transition::Pop;
+            }
+            if ui.button(vec2(85.0, 125.0), "Restart") {
+                transition = Transition::Push(Box::new(Confirm::new(ConfirmAction::Restart)));
+            }
+            if ui.button(vec2(85.0, 225.0), "Options") {
+                transition = Transition::Push(Box::new(Options));
+            }
+            if ui.button(vec2(55.0, 325.0), "Main Menu") {
+                transition = Transition::Push(Box::new(Confirm::new(ConfirmAction::MainMenu)));
+            }
+        });
+        if is_key_pressed(KeyCode::Escape) {
+            transition = Transition::Pop;
+        }
+        transition
+    }
+
+    fn draw(&mut self, _ctx: &Context) {
+        dim_background();
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,102 @@
+use super::{centered, dim_background, Context};
+use crate::scene::{Scene, Transition};
+use crate::versus::Match;
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+/// Who new runs are played by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Players {
+    #[default]
+    Solo,
+    /// Two ships on the same side.
+    Coop,
+    /// Two ships racing each other for points.
+    Versus,
+}
+
+impl Players {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Players::Solo => "Solo",
+            Players::Coop => "Co-op",
+            Players::Versus => "Versus",
+        }
+    }
+
+    pub fn next(&self) -> Players {
+        match self {
+            Players::Solo => Players::Coop,
+            Players::Coop => Players::Versus,
+            Players::Versus => Players::Solo,
+        }
+    }
+}
+
+/// Sets up two-player runs: co-op with shared or separate lives and friendly
+/// fire, or a versus match.
+pub struct PlayersMenu;
+
+impl Scene for PlayersMenu {
+    fn update(&mut self, ctx: &mut Context, _delta: f32) -> Transition {
+        let window_size = vec2(780.0, 560.0);
+        let mut transition = Transition::None;
+        root_ui().window(hash!(), centered(window_size), window_size, |ui| {
+            ui.label(vec2(310.0, -34.0), "Players");
+            ui.label(vec2(25.0, 50.0), "Players");
+            if ui.button(vec2(420.0, 25.0), ctx.players.label()) {
+                ctx.players = ctx.players.next();
+            }
+            match ctx.players {
+                Players::Solo => {}
+                Players::Coop => {
+                    ui.label(vec2(25.0, 140.0), "Lives");
+                    let lives_label = if ctx.coop.shared_lives {
+                        "Shared"
+                    } else {
+                        "Own"
+                    };
+                    if ui.button(vec2(420.0, 115.0), lives_label) {
+                        ctx.coop.shared_lives = !ctx.coop.shared_lives;
+                    }
+                    ui.label(vec2(25.0, 230.0), "Friendly fire");
+                    let fire_label = if ctx.coop.friendly_fire { "On" } else { "Off" };
+                    if ui.button(vec2(420.0, 205.0), fire_label) {
+                        ctx.coop.friendly_fire = !ctx.coop.friendly_fire;
+                    }
+                }
+                Players::Versus => {
+                    ui.label(vec2(25.0, 140.0), "Best of");
+                    let best_of = ctx.versus_match.best_of.to_string();
+                    if ui.button(vec2(420.0, 115.0), best_of.as_str()) {
+                        ctx.versus_match = Match::new(ctx.versus_match.next_best_of());
+                    }
+                    ui.label(vec2(25.0, 230.0), "Garbage");
+                    let garbage_label = if ctx.versus.garbage { "On" } else { "Off" };
+                    if ui.button(vec2(420.0, 205.0), garbage_label) {
+                        ctx.versus.garbage = !ctx.versus.garbage;
+                    }
+                }
+            }
+            if ctx.players != Players::Solo {
+                ui.label(vec2(25.0, 300.0), "P1: arrows + Space");
+                ui.label(vec2(25.0, 335.0), "P2: WASD + Left Shift");
+            }
+            if ui.button(vec2(285.0, 385.0), "Back") {
+                transition = Transition::Pop;
+            }
+        });
+        if is_key_pressed(KeyCode::Escape) {
+            transition = Transition::Pop;
+        }
+        transition
+    }
+
+    fn draw(&mut self, _ctx: &Context) {
+        dim_background();
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,114 @@
+use super::{centered, Context, Gallery, Gameplay};
+use crate::enemy::EnemyKind;
+use crate::lifetime::SCORE_BUCKET;
+use crate::scene::{Scene, Transition};
+use macroquad::prelude::*;
+use macroquad::ui::{hash, root_ui};
+
+/// Lifetime statistics and a histogram of run scores, with the way to the
+/// achievement gallery and to the last run's replay.
+pub struct Statistics;
+
+impl Scene for Statistics {
+    fn update(&mut self, ctx: &mut Context, _delta: f32) -> Transition {
+        let window_size = vec2(620.0, 120.0);
+        let position = vec2(
+            centered(window_size).x,
+            screen_height() - window_size.y - 20.0,
+        );
+        let mut transition = Transition::None;
+        root_ui().window(hash!(), position, window_size, |ui| {
+            if ui.button(vec2(55.0, 5.0), "Back") {
+                transition = Transition::Pop;
+            }
+            if ui.button(vec2(320.0, 5.0), "Awards") {
+                transition = Transition::Push(Box::new(Gallery));
+            }
+        });
+        if is_key_pressed(KeyCode::Escape) {
+            transition = Transition::Pop;
+        }
+        if is_key_pressed(KeyCode::R) {
+            if let Some(replay) = &ctx.last_replay {
+                transition = Transition::Push(Box::new(Gameplay::replay(ctx, replay.clone())));
+            }
+        }
+        transition
+    }
+
+    fn draw(&mut self, ctx: &Context) {
+        clear_background(BLACK);
+        let stats = &ctx.lifetime;
+        let seconds = stats.play_time as u64;
+        let accuracy = if stats.shots_fired == 0 {
+            0.0
+        } else {
+            stats.shots_hit as f32 / stats.shots_fired as f32 * 100.0
+        };
+        let mut lines = vec![
+            format!(
+                "Play time {}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            ),
+            format!("Runs {}", stats.runs),
+            format!("Shots {} ({:.0}% hit)", stats.shots_fired, accuracy),
+            format!("Best chain {}", stats.best_chain),
+        ];
+        if stats.friendly_deaths > 0 {
+            lines.push(format!("Shot down by a friend {}", stats.friendly_deaths));
+        }
+        for kind in EnemyKind::ALL {
+            lines.push(format!(
+                "{}: {} kills, {} deaths",
+                kind.name(),
+                stats.kills_of(kind),
+                stats.deaths_to(kind)
+            ));
+        }
+
+        if ctx.last_replay.is_some() {
+            lines.push("Press R to watch the last run".to_string());
+        }
+
+        draw_text("Statistics", 40.0, 60.0, 50.0, WHITE);
+        for (row, line) in lines.iter().enumerate() {
+            draw_text(line, 40.0, 110.0 + row as f32 * 30.0, 28.0, WHITE);
+        }
+
+        // Score histogram, one bar per bucket from zero to the best bucket.
+        let top = 110.0 + lines.len() as f32 * 30.0 + 20.0;
+        draw_text("Scores", 40.0, top, 28.0, WHITE);
+        let Some(&last_bucket) = stats.scores.keys().next_back() else {
+            draw_text("No runs yet", 40.0, top + 30.0, 25.0, GRAY);
+            return;
+        };
+        let buckets = (last_bucket / SCORE_BUCKET + 1) as usize;
+        let tallest = stats.scores.values().copied().max().unwrap_or(1) as f32;
+        let area = Rect::new(40.0, top + 15.0, screen_width() - 80.0, 80.0);
+        let bar_width = area.w / buckets as f32;
+        for index in 0..buckets {
+            let bucket = index as u32 * SCORE_BUCKET;
+            let count = stats.scores.get(&bucket).copied().unwrap_or(0);
+            let height = area.h * count as f32 / tallest;
+            draw_rectangle(
+                area.x + index as f32 * bar_width + 1.0,
+                area.y + area.h - height,
+                (bar_width - 2.0).max(1.0),
+                height,
+                YELLOW,
+            );
+        }
+        draw_text("0", area.x, area.y + area.h + 20.0, 20.0, GRAY);
+        let end = format!("{}", last_bucket + SCORE_BUCKET);
+        let end_dimensions = measure_text(&end, None, 20, 1.0);
+        draw_text(
+            &end,
+            area.x + area.w - end_dimensions.width,
+            area.y + area.h + 20.0,
+            20.0,
+            GRAY,
+        );
+    }
+}
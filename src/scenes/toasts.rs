@@ -0,0 +1,48 @@
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+/// Seconds each toast stays on screen.
+const TOAST_TIME: f32 = 3.0;
+/// Seconds a toast takes to slide in and out.
+const TOAST_SLIDE: f32 = 0.3;
+
+/// Notifications shown one at a time at the top of the screen, over every
+/// scene.
+#[derive(Default)]
+pub struct Toasts {
+    queue: VecDeque<(String, String)>,
+    timer: f32,
+}
+
+impl Toasts {
+    pub fn push(&mut self, title: &str, text: &str) {
+        self.queue.push_back((title.to_string(), text.to_string()));
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        if self.queue.is_empty() {
+            return;
+        }
+        self.timer += delta;
+        if self.timer >= TOAST_TIME {
+            self.timer = 0.0;
+            self.queue.pop_front();
+        }
+    }
+
+    pub fn draw(&self) {
+        let Some((title, text)) = self.queue.front() else {
+            return;
+        };
+        let size = vec2(420.0, 70.0);
+        let slide = (self.timer / TOAST_SLIDE)
+            .min((TOAST_TIME - self.timer) / TOAST_SLIDE)
+            .clamp(0.0, 1.0);
+        let x = screen_width() / 2.0 - size.x / 2.0;
+        let y = -size.y + (size.y + 10.0) * slide;
+        draw_rectangle(x, y, size.x, size.y, Color::new(0.0, 0.0, 0.0, 0.8));
+        draw_rectangle_lines(x, y, size.x, size.y, 2.0, YELLOW);
+        draw_text(title, x + 15.0, y + 30.0, 28.0, YELLOW);
+        draw_text(text, x + 15.0, y + 55.0, 22.0, WHITE);
+    }
+}
@@ -0,0 +1,219 @@
+//! Combo multiplier and chain scoring.
+//!
+//! Every kill extends the current chain and refreshes the combo window. Each
+//! `kills_per_step` kills in a chain raise the multiplier by one, up to
+//! `max_multiplier`. When the window runs out the multiplier decays one step
+//! at a time instead of dropping straight back to x1, while taking damage or
+//! missing too many shots in a row breaks the chain outright.
+
+#[derive(Clone, Copy, Debug)]
+pub struct ComboConfig {
+    /// Seconds a chain stays alive after the last kill.
+    pub window: f32,
+    pub kills_per_step: u32,
+    pub max_multiplier: u32,
+    /// Consecutive missed shots that break the chain.
+    pub misses_to_break: u32,
+}
+
+impl Default for ComboConfig {
+    fn default() -> Self {
+        ComboConfig {
+            window: 2.0,
+            kills_per_step: 3,
+            max_multiplier: 8,
+            misses_to_break: 3,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Combo {
+    config: ComboConfig,
+    chain: u32,
+    timer: f32,
+    misses: u32,
+    best_chain: u32,
+}
+
+impl Combo {
+    pub fn new(config: ComboConfig) -> Self {
+        Combo {
+            config,
+            chain: 0,
+            timer: 0.0,
+            misses: 0,
+            best_chain: 0,
+        }
+    }
+
+    pub fn chain(&self) -> u32 {
+        self.chain
+    }
+
+    pub fn best_chain(&self) -> u32 {
+        self.best_chain
+    }
+
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.chain / self.config.kills_per_step).min(self.config.max_multiplier)
+    }
+
+    /// Fraction of the combo window left, for drawing the meter.
+    pub fn meter(&self) -> f32 {
+        if self.chain == 0 {
+            0.0
+        } else {
+            (self.timer / self.config.window).clamp(0.0, 1.0)
+        }
+    }
+
+    /// Counts a kill worth `base` points and returns the points to award.
+    pub fn register_kill(&mut self, base: u32) -> u32 {
+        self.chain += 1;
+        self.timer = self.config.window;
+        self.misses = 0;
+        self.best_chain = self.best_chain.max(self.chain);
+        base * self.multiplier()
+    }
+
+    pub fn register_miss(&mut self) {
+        self.misses += 1;
+        if self.misses >= self.config.misses_to_break {
+            self.break_chain();
+        }
+    }
+
+    pub fn break_chain(&mut self) {
+        self.chain = 0;
+        self.timer = 0.0;
+        self.misses = 0;
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        if self.chain == 0 {
+            return;
+        }
+        self.timer -= delta;
+        while self.timer <= 0.0 && self.chain > 0 {
+            // Drop to the first kill of the previous multiplier step.
+            self.chain = self.multiplier().saturating_sub(2) * self.config.kills_per_step;
+            if self.chain == 0 {
+                self.timer = 0.0;
+            } else {
+                self.timer += self.config.window;
+            }
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Combo::new(self.config);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A combo with `kills` kills in its chain.
+    fn combo_with(kills: u32) -> Combo {
+        let mut combo = Combo::new(ComboConfig::default());
+        for _ in 0..kills {
+            combo.register_kill(10);
+        }
+        combo
+    }
+
+    #[test]
+    fn kills_raise_the_multiplier_every_step() {
+        let mut combo = combo_with(0);
+        assert_eq!(combo.register_kill(10), 10);
+        assert_eq!(combo.register_kill(10), 10);
+        assert_eq!(combo.register_kill(10), 20);
+        assert_eq!(combo.multiplier(), 2);
+        assert_eq!(combo.chain(), 3);
+    }
+
+    #[test]
+    fn multiplier_is_capped() {
+        let config = ComboConfig::default();
+        let mut combo = combo_with(config.kills_per_step * config.max_multiplier * 2);
+        assert_eq!(combo.multiplier(), config.max_multiplier);
+        assert_eq!(combo.register_kill(10), 10 * config.max_multiplier);
+    }
+
+    #[test]
+    fn chain_holds_within_the_window() {
+        let mut combo = combo_with(7);
+        combo.update(1.9);
+        assert_eq!(combo.chain(), 7);
+        assert_eq!(combo.multiplier(), 3);
+    }
+
+    #[test]
+    fn multiplier_decays_one_step_per_window() {
+        let mut combo = combo_with(7);
+        combo.update(2.0);
+        assert_eq!(combo.multiplier(), 2);
+        assert_eq!(combo.chain(), 3);
+        combo.update(2.0);
+        assert_eq!(combo.multiplier(), 1);
+        assert_eq!(combo.chain(), 0);
+        assert_eq!(combo.meter(), 0.0);
+    }
+
+    #[test]
+    fn a_long_gap_decays_several_steps_at_once() {
+        let mut combo = combo_with(12);
+        assert_eq!(combo.multiplier(), 5);
+        combo.update(4.5);
+        assert_eq!(combo.multiplier(), 3);
+        combo.update(100.0);
+        assert_eq!(combo.multiplier(), 1);
+        assert_eq!(combo.chain(), 0);
+    }
+
+    #[test]
+    fn a_kill_refreshes_the_window() {
+        let mut combo = combo_with(3);
+        combo.update(1.5);
+        combo.register_kill(10);
+        combo.update(1.5);
+        assert_eq!(combo.chain(), 4);
+        assert_eq!(combo.multiplier(), 2);
+    }
+
+    #[test]
+    fn misses_break_the_chain_at_the_limit() {
+        let mut combo = combo_with(6);
+        combo.register_miss();
+        combo.register_miss();
+        assert_eq!(combo.chain(), 6);
+        combo.register_miss();
+        assert_eq!(combo.chain(), 0);
+        assert_eq!(combo.multiplier(), 1);
+    }
+
+    #[test]
+    fn a_kill_forgives_earlier_misses() {
+        let mut combo = combo_with(6);
+        combo.register_miss();
+        combo.register_miss();
+        combo.register_kill(10);
+        combo.register_miss();
+        combo.register_miss();
+        assert_eq!(combo.chain(), 7);
+    }
+
+    #[test]
+    fn breaking_the_chain_resets_it_but_keeps_the_best() {
+        let mut combo = combo_with(9);
+        combo.break_chain();
+        assert_eq!(combo.chain(), 0);
+        assert_eq!(combo.multiplier(), 1);
+        assert_eq!(combo.meter(), 0.0);
+        assert_eq!(combo.best_chain(), 9);
+        combo.register_kill(10);
+        assert_eq!(combo.chain(), 1);
+    }
+}
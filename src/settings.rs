@@ -0,0 +1,66 @@
+//! Player settings.
+//!
+//! Stored as plain text, one `key value` pair per line like the leaderboard.
+//! Unknown keys and unparsable values are ignored, so a hand-edited or older
+//! file still loads.
+
+use std::fs;
+
+pub const SETTINGS_PATH: &str = "settings.dat";
+
+/// Volume steps the options screen cycles through.
+const VOLUME_STEP: f32 = 0.2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Settings {
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            music_volume: 1.0,
+            sfx_volume: 1.0,
+        }
+    }
+}
+
+impl Settings {
+    pub fn load(path: &str) -> Self {
+        let mut settings = Settings::default();
+        if let Ok(contents) = fs::read_to_string(path) {
+            for line in contents.lines() {
+                let mut parts = line.split_whitespace();
+                let (Some(key), Some(value)) = (parts.next(), parts.next()) else {
+                    continue;
+                };
+                match (key, value.parse::<f32>()) {
+                    ("music_volume", Ok(volume)) => settings.music_volume = volume.clamp(0.0, 1.0),
+                    ("sfx_volume", Ok(volume)) => settings.sfx_volume = volume.clamp(0.0, 1.0),
+                    _ => {}
+                }
+            }
+        }
+        settings
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        fs::write(
+            path,
+            format!(
+                "music_volume {}\nsfx_volume {}\n",
+                self.music_volume, self.sfx_volume
+            ),
+        )
+    }
+}
+
+/// The next volume step after `volume`, wrapping from full back to silent.
+pub fn next_volume(volume: f32) -> f32 {
+    if volume >= 1.0 - VOLUME_STEP / 2.0 {
+        0.0
+    } else {
+        ((volume / VOLUME_STEP).round() + 1.0) * VOLUME_STEP
+    }
+}
@@ -0,0 +1,108 @@
+//! Colliders for the sprites the simulation collides.
+//!
+//! Colliders are authored in pixels of a single frame, relative to the frame
+//! centre, and scaled to the size the sprite is drawn at. The frame size comes
+//! from the built-in manifest; `validate-assets` checks that the assets folder
+//! keeps the frame each collider was drawn for.
+
+use crate::collider::Collider;
+use crate::enemy::EnemyKind;
+use crate::manifest::Manifest;
+use macroquad::prelude::*;
+
+pub struct SpriteDef {
+    /// The manifest's key for the sheet.
+    pub key: &'static str,
+    pub collider: Collider,
+}
+
+impl SpriteDef {
+    /// The frame the collider is drawn for, from the built-in manifest.
+    pub fn frame_size(&self) -> Vec2 {
+        Manifest::builtin()
+            .sprite(self.key)
+            .expect("the built-in manifest has every collided sprite")
+            .frame_size()
+    }
+
+    /// The collider for this sprite drawn at `draw_size` pixels.
+    pub fn collider(&self, draw_size: Vec2) -> Collider {
+        self.collider.scaled(draw_size / self.frame_size())
+    }
+}
+
+pub static SHIP: SpriteDef = SpriteDef {
+    key: "ship",
+    collider: Collider::polygon(&[
+        vec2(-2.0, -12.0),
+        vec2(2.0, -12.0),
+        vec2(8.0, -6.0),
+        vec2(8.0, 1.0),
+        vec2(2.0, 11.0),
+        vec2(-2.0, 11.0),
+        vec2(-8.0, 1.0),
+        vec2(-8.0, -6.0),
+    ]),
+};
+
+pub static BULLET: SpriteDef = SpriteDef {
+    key: "bullet",
+    collider: Collider::Capsule {
+        a: vec2(0.5, -3.5),
+        b: vec2(0.5, 3.5),
+        radius: 2.5,
+    },
+};
+
+pub static ENEMY_SMALL: SpriteDef = SpriteDef {
+    key: "enemy_small",
+    collider: Collider::polygon(&[
+        vec2(-1.5, -8.0),
+        vec2(-0.5, -8.0),
+        vec2(7.5, -3.0),
+        vec2(7.5, 5.0),
+        vec2(1.5, 8.0),
+        vec2(-2.5, 8.0),
+        vec2(-8.5, 5.0),
+        vec2(-8.5, -3.0),
+    ]),
+};
+
+pub static ENEMY_MEDIUM: SpriteDef = SpriteDef {
+    key: "enemy_medium",
+    collider: Collider::polygon(&[
+        vec2(-1.0, -8.0),
+        vec2(1.0, -8.0),
+        vec2(15.0, 1.0),
+        vec2(16.0, 4.0),
+        vec2(12.0, 7.0),
+        vec2(-12.0, 7.0),
+        vec2(-16.0, 4.0),
+        vec2(-15.0, 1.0),
+    ]),
+};
+
+pub static ENEMY_BIG: SpriteDef = SpriteDef {
+    key: "enemy_big",
+    collider: Collider::polygon(&[
+        vec2(-1.0, -14.0),
+        vec2(0.0, -14.0),
+        vec2(9.0, -1.0),
+        vec2(13.0, 6.0),
+        vec2(12.0, 14.0),
+        vec2(-12.0, 14.0),
+        vec2(-13.0, 6.0),
+        vec2(-9.0, -1.0),
+    ]),
+};
+
+pub fn enemy(kind: EnemyKind) -> &'static SpriteDef {
+    match kind {
+        EnemyKind::Small => &ENEMY_SMALL,
+        EnemyKind::Medium => &ENEMY_MEDIUM,
+        EnemyKind::Big => &ENEMY_BIG,
+    }
+}
+
+/// Every sprite with a collider.
+pub static ALL: [&SpriteDef; 5] = [&SHIP, &BULLET, &ENEMY_SMALL, &ENEMY_MEDIUM, &ENEMY_BIG];
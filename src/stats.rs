@@ -0,0 +1,50 @@
+//! Statistics gathered over a single run.
+
+use crate::adaptive::Adjustment;
+use crate::enemy::EnemyKind;
+use crate::events::{Event, Subscriber};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Default)]
+pub struct RunStats {
+    pub time_survived: f32,
+    pub shots_fired: u32,
+    /// Shots that hit at least one enemy.
+    pub shots_hit: u32,
+    pub kills: u32,
+    pub kills_by_kind: BTreeMap<EnemyKind, u32>,
+    /// Enemies that passed the ship within a few pixels without hitting it.
+    pub near_misses: u32,
+    /// Every change made by adaptive difficulty, in order.
+    pub adjustments: Vec<Adjustment>,
+}
+
+impl RunStats {
+    /// Fraction of fired shots that hit something, 0.0 before the first shot.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            self.shots_hit as f32 / self.shots_fired as f32
+        }
+    }
+
+    pub fn kills_of(&self, kind: EnemyKind) -> u32 {
+        self.kills_by_kind.get(&kind).copied().unwrap_or(0)
+    }
+}
+
+impl Subscriber for RunStats {
+    fn notify(&mut self, event: &Event) {
+        match event {
+            Event::ShotFired { .. } => self.shots_fired += 1,
+            Event::ShotExpired { hit: true } => self.shots_hit += 1,
+            Event::EnemyKilled { kind, .. } => {
+                self.kills += 1;
+                *self.kills_by_kind.entry(*kind).or_default() += 1;
+            }
+            Event::NearMiss { .. } => self.near_misses += 1,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,554 @@
+//! Gameplay systems, run by [`World::step`] in the order they appear here.
+
+use crate::ecs::Entity;
+use crate::enemy::EnemyKind;
+use crate::events::Event;
+use crate::hits::{self, Overlap};
+use crate::versus::{self, GARBAGE_CHAIN, HIT_PENALTY};
+use crate::world::{Input, World, MAX_PLAYERS, NEAR_MISS_MARGIN};
+use macroquad::prelude::*;
+
+/// Turns each player's input into ship velocity and new projectiles.
+pub fn input(world: &mut World, inputs: &[Input], delta: f32) {
+    for index in 0..world.ships.len() {
+        let ship = world.ships[index];
+        let input = match inputs.get(index) {
+            Some(input) if world.ship_alive(ship) => *input,
+            _ => Input::default(),
+        };
+        steer(world, ship, &input, delta);
+    }
+}
+
+fn steer(world: &mut World, player: Entity, input: &Input, delta: f32) {
+    let (Some(ship), Some(transform)) =
+        (world.players.get_mut(player), world.transforms.get(player))
+    else {
+        return;
+    };
+    let params = world.params;
+    let mut movement = input.movement.clamp(Vec2::splat(-1.0), Vec2::splat(1.0));
+    if params.mirror_controls {
+        movement.x = -movement.x;
+    }
+    if movement != ship.movement && movement != Vec2::ZERO {
+        world.events.publish(Event::ShipMoved { movement });
+    }
+    ship.movement = movement;
+    ship.fire_cooldown = (ship.fire_cooldown - delta).max(0.0);
+    let fire = input.fire && ship.fire_cooldown <= 0.0;
+    let speed = ship.speed;
+    let muzzle = transform.pos - vec2(0.0, 24.0);
+    if let Some(velocity) = world.velocities.get_mut(player) {
+        let target = movement * speed;
+        velocity.vel = match params.player_drift {
+            Some(acceleration) => {
+                velocity.vel + (target - velocity.vel).clamp_length_max(acceleration * delta)
+            }
+            None => target,
+        };
+    }
+
+    if fire && world.spawn_projectile(player, muzzle).is_some() {
+        if let Some(ship) = world.players.get_mut(player) {
+            ship.fire_cooldown = params.fire_cooldown;
+        }
+        world.events.publish(Event::ShotFired { pos: muzzle });
+    }
+}
+
+/// Spawns enemies following the difficulty curve.
+pub fn ai(world: &mut World, delta: f32) {
+    let difficulty = world.difficulty;
+    let progress = difficulty.progress(world.stats.time_survived, world.score);
+    let params = world.params;
+    let chance =
+        difficulty.spawn_chance(progress, delta) * world.spawn_scale * params.spawn_rate_scale;
+    if world.rng.gen_range(0.0, 1.0) >= chance {
+        return;
+    }
+    let kind = difficulty.pick_kind(progress, world.rng.gen_range(0.0, 1.0));
+    let (min_size, max_size) = params.enemy_size(kind);
+    let size = world.rng.gen_range(min_size, max_size);
+    let size = if params.giant_enemies { max_size } else { size };
+    let (min_speed, max_speed) = difficulty.speed_range(progress);
+    let speed =
+        world.rng.gen_range(min_speed, max_speed) * world.speed_scale * params.enemy_speed_scale;
+    let x = world
+        .rng
+        .gen_range(size / 2.0, (world.arena.x - size / 2.0).max(size / 2.0));
+    world.spawn_enemy(kind, vec2(x, -size), size, speed);
+}
+
+/// Integrates velocities, keeps ships inside the arena and counts enemies
+/// that slip past a ship without touching it.
+pub fn movement(world: &mut World, delta: f32) {
+    let mut ships = [None; MAX_PLAYERS];
+    for (slot, &ship) in ships.iter_mut().zip(&world.ships) {
+        if let (true, Some(body)) = (world.ship_alive(ship), world.bodies.get(ship)) {
+            *slot = Some((world.ship_pos(ship), body.collider));
+        }
+    }
+
+    for (entity, velocity) in world.velocities.iter() {
+        let Some(transform) = world.transforms.get_mut(entity) else {
+            continue;
+        };
+        let previous_y = transform.pos.y;
+        transform.pos += velocity.vel * delta;
+
+        if world.players.contains(entity) {
+            transform.pos = transform.pos.clamp(Vec2::ZERO, world.arena);
+        } else if let (Some(enemy), Some(body)) =
+            (world.enemies.get(entity), world.bodies.get(entity))
+        {
+            // Measured between the colliders, which for the ship is twice
+            // its sprite's size. An enemy that touches the ship is a hit.
+            let width = body.bounds(transform.pos).w;
+            let near_miss = ships.iter().flatten().any(|(ship_pos, ship_collider)| {
+                previous_y < ship_pos.y
+                    && transform.pos.y >= ship_pos.y
+                    && (transform.pos.x - ship_pos.x).abs()
+                        < (width + ship_collider.bounds(*ship_pos).w) / 2.0 + NEAR_MISS_MARGIN
+                    && !ship_collider.intersects(*ship_pos, &body.collider, transform.pos)
+            });
+            if near_miss {
+                world.events.publish(Event::NearMiss { kind: enemy.kind });
+            }
+        }
+    }
+}
+
+/// Finds projectile/enemy overlaps, enemies touching ships and, with friendly
+/// fire, projectiles touching the other ship.
+pub fn collision(world: &mut World) {
+    world
+        .grid
+        .reset(Rect::new(0.0, 0.0, world.arena.x, world.arena.y));
+    world.grid_entities.clear();
+    for (entity, _) in world.enemies.iter() {
+        if let (Some(transform), Some(body)) =
+            (world.transforms.get(entity), world.bodies.get(entity))
+        {
+            world
+                .grid
+                .insert(world.grid_entities.len(), body.bounds(transform.pos));
+            world.grid_entities.push(entity);
+        }
+    }
+
+    world.player_contacts.clear();
+    for &ship in &world.ships {
+        let (Some(transform), Some(body), true) = (
+            world.transforms.get(ship),
+            world.bodies.get(ship),
+            world.healths.get(ship).is_some_and(|health| !health.dead()),
+        ) else {
+            continue;
+        };
+        world
+            .grid
+            .query(body.bounds(transform.pos), &mut world.candidates);
+        for &index in &world.candidates {
+            let enemy = world.grid_entities[index];
+            let (Some(enemy_transform), Some(enemy_body)) =
+                (world.transforms.get(enemy), world.bodies.get(enemy))
+            else {
+                continue;
+            };
+            if body
+                .collider
+                .intersects(transform.pos, &enemy_body.collider, enemy_transform.pos)
+            {
+                world.player_contacts.push((ship, enemy));
+            }
+        }
+    }
+
+    world.overlaps.clear();
+    for (projectile, _) in world.projectiles.iter() {
+        let (Some(transform), Some(body)) = (
+            world.transforms.get(projectile),
+            world.bodies.get(projectile),
+        ) else {
+            continue;
+        };
+        world
+            .grid
+            .query(body.bounds(transform.pos), &mut world.candidates);
+        for &index in &world.candidates {
+            let target = world.grid_entities[index];
+            let (Some(target_transform), Some(target_body)) =
+                (world.transforms.get(target), world.bodies.get(target))
+            else {
+                continue;
+            };
+            if body
+                .collider
+                .intersects(transform.pos, &target_body.collider, target_transform.pos)
+            {
+                world.overlaps.push(Overlap {
+                    projectile,
+                    target,
+                    distance: transform.pos.distance(target_transform.pos),
+                });
+            }
+        }
+    }
+
+    world.friendly_overlaps.clear();
+    if !world.params.coop.is_some_and(|coop| coop.friendly_fire) {
+        return;
+    }
+    for (projectile, shot) in world.projectiles.iter() {
+        let (Some(transform), Some(body)) = (
+            world.transforms.get(projectile),
+            world.bodies.get(projectile),
+        ) else {
+            continue;
+        };
+        for &ship in &world.ships {
+            if ship == shot.owner || !world.ship_alive(ship) {
+                continue;
+            }
+            let (Some(ship_transform), Some(ship_body)) =
+                (world.transforms.get(ship), world.bodies.get(ship))
+            else {
+                continue;
+            };
+            if body
+                .collider
+                .intersects(transform.pos, &ship_body.collider, ship_transform.pos)
+            {
+                world.friendly_overlaps.push((projectile, ship));
+            }
+        }
+    }
+}
+
+/// Applies projectile hits and contact damage, and scores kills for the
+/// ship that fired. In versus, kill chains send garbage to the opponent.
+pub fn damage(world: &mut World, delta: f32) {
+    world.hits.clear();
+    hits::resolve(
+        &mut world.overlaps,
+        &world.entities,
+        &mut world.projectiles,
+        &mut world.healths,
+        &mut world.hits,
+    );
+    for index in 0..world.hits.len() {
+        let hit = world.hits[index];
+        if !hit.killed {
+            continue;
+        }
+        let (Some(enemy), Some(transform), Some(body)) = (
+            world.enemies.get(hit.target),
+            world.transforms.get(hit.target),
+            world.bodies.get(hit.target),
+        ) else {
+            continue;
+        };
+        let owner = world
+            .projectiles
+            .get(hit.projectile)
+            .map(|projectile| projectile.owner);
+        let player = owner
+            .and_then(|owner| world.players.get(owner))
+            .map_or(0, |player| player.index);
+        let combo = &mut world.combos[player];
+        let points = combo.register_kill(body.size.round() as u32);
+        let chain = combo.chain();
+        world.score += points;
+        world.events.publish(Event::EnemyKilled {
+            kind: enemy.kind,
+            pos: transform.pos,
+            size: body.size,
+            points,
+            chain,
+        });
+        let Some(ship) = owner.and_then(|owner| world.players.get_mut(owner)) else {
+            continue;
+        };
+        ship.score += points;
+        ship.chain += 1;
+        let (from, chain) = (ship.index, ship.chain);
+        let garbage = world.params.versus.is_some_and(|versus| versus.garbage);
+        if garbage && chain.is_multiple_of(GARBAGE_CHAIN) {
+            versus::send_garbage(world, from);
+        }
+    }
+
+    for index in 0..world.ships.len() {
+        let ship = world.ships[index];
+        if let Some(health) = world.healths.get_mut(ship) {
+            health.invulnerable = (health.invulnerable - delta).max(0.0);
+        }
+        let contact = world
+            .player_contacts
+            .iter()
+            .filter(|&&(touching, _)| touching == ship)
+            .find_map(|&(_, enemy)| Some((enemy, world.enemies.get(enemy)?.kind)));
+        // An enemy that lands a hit crashes into the ship, so a contact costs
+        // one hit point rather than one for every frame the two overlap.
+        if let Some((enemy, by)) = contact {
+            if hit_ship(world, ship, Some(by)) {
+                world.despawn(enemy);
+            }
+        }
+    }
+    for index in 0..world.friendly_overlaps.len() {
+        let (projectile, ship) = world.friendly_overlaps[index];
+        let Some(shot) = world.projectiles.get_mut(projectile) else {
+            continue;
+        };
+        if !shot.spent() {
+            shot.spend();
+            hit_ship(world, ship, None);
+        }
+    }
+}
+
+/// Takes a hit point from `ship`, or from both ships when they share their
+/// lives, unless it was hit moments ago. Versus ships never go down; a hit
+/// costs points instead. The run is over once no ship is left. Returns
+/// whether the hit landed.
+fn hit_ship(world: &mut World, ship: Entity, by: Option<EnemyKind>) -> bool {
+    let health = world.ship_health(ship);
+    if health.invulnerable > 0.0 || health.dead() {
+        return false;
+    }
+    let shared = world.params.coop.is_some_and(|coop| coop.shared_lives);
+    if let Some(player) = world.players.get(ship) {
+        world.combos[player.index].break_chain();
+    }
+    for index in 0..world.ships.len() {
+        let target = world.ships[index];
+        if target != ship && !shared {
+            continue;
+        }
+        let pos = world.ship_pos(target);
+        let Some(health) = world.healths.get_mut(target) else {
+            continue;
+        };
+        if health.dead() {
+            continue;
+        }
+        let versus = world.params.versus.is_some();
+        if !versus {
+            health.hp -= 1;
+        }
+        health.invulnerable = world.params.player_invulnerability;
+        let hp = health.hp;
+        if let Some(player) = world.players.get_mut(target) {
+            player.chain = 0;
+            if versus {
+                player.score = player.score.saturating_sub(HIT_PENALTY);
+            }
+        }
+        world.events.publish(Event::PlayerHit {
+            player: index,
+            pos,
+            hp,
+            by,
+        });
+        if hp == 0 {
+            world.events.publish(Event::PlayerDied {
+                player: index,
+                pos,
+                by,
+            });
+        }
+    }
+    if !world.ships.iter().any(|&ship| world.ship_alive(ship)) {
+        world.game_over = true;
+    }
+    true
+}
+
+/// Despawns dead enemies and anything that left the arena, and settles
+/// whether each finished projectile hit or missed.
+pub fn cleanup(world: &mut World) {
+    world.doomed.clear();
+    for (entity, _) in world.enemies.iter() {
+        let gone = world
+            .healths
+            .get(entity)
+            .is_some_and(|health| health.dead())
+            || world.transforms.get(entity).is_some_and(|transform| {
+                let size = world.bodies.get(entity).map_or(0.0, |body| body.size);
+                transform.pos.y >= world.arena.y + size
+            });
+        if gone {
+            world.doomed.push(entity);
+        }
+    }
+    for (entity, projectile) in world.projectiles.iter() {
+        let offscreen = world.transforms.get(entity).is_some_and(|transform| {
+            let size = world.bodies.get(entity).map_or(0.0, |body| body.size);
+            transform.pos.y <= -size / 2.0
+        });
+        if projectile.spent() || offscreen {
+            if !projectile.has_hit() {
+                let player = world
+                    .players
+                    .get(projectile.owner)
+                    .map_or(0, |player| player.index);
+                world.combos[player].register_miss();
+            }
+            world.events.publish(Event::ShotExpired {
+                hit: projectile.has_hit(),
+            });
+            world.doomed.push(entity);
+        }
+    }
+    for index in 0..world.doomed.len() {
+        let entity = world.doomed[index];
+        world.despawn(entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::Preset;
+    use crate::pool::PoolCaps;
+    use crate::world::{Coop, SimParams};
+
+    const DELTA: f32 = 1.0 / 60.0;
+    const ENEMY_SIZE: f32 = 20.0;
+
+    /// Flies one small enemy down past the ship, `offset` to its right, and
+    /// returns whether that was a near miss and whether the ship died.
+    fn pass_by(offset: f32) -> (bool, bool) {
+        let params = SimParams {
+            spawn_rate_scale: 0.0,
+            ..SimParams::default()
+        };
+        let mut world = World::new(
+            vec2(800.0, 600.0),
+            Preset::Normal.difficulty(),
+            PoolCaps::default(),
+            1,
+            params,
+        );
+        let ship = world.ships[0];
+        let pos = world.ship_pos(ship);
+        world.spawn_enemy(
+            EnemyKind::Small,
+            pos + vec2(offset, -ENEMY_SIZE),
+            ENEMY_SIZE,
+            300.0,
+        );
+        let (mut near_miss, mut died) = (false, false);
+        for _ in 0..10 {
+            for event in world.step(&[Input::default()], DELTA).iter() {
+                match event {
+                    Event::NearMiss { .. } => near_miss = true,
+                    Event::PlayerDied { .. } => died = true,
+                    _ => {}
+                }
+            }
+        }
+        (near_miss, died)
+    }
+
+    fn ship_width() -> f32 {
+        let world = World::new(
+            vec2(800.0, 600.0),
+            Preset::Normal.difficulty(),
+            PoolCaps::default(),
+            1,
+            SimParams::default(),
+        );
+        let ship = world.ships[0];
+        world.bodies.get(ship).unwrap().bounds(Vec2::ZERO).w
+    }
+
+    #[test]
+    fn an_enemy_just_past_the_collider_is_a_near_miss() {
+        let offset = (ship_width() + ENEMY_SIZE) / 2.0 + NEAR_MISS_MARGIN / 2.0;
+        assert_eq!(pass_by(offset), (true, false));
+    }
+
+    #[test]
+    fn the_margin_is_measured_from_the_collider_not_the_sprite() {
+        // Outside the margin around the sprite, but inside it around the
+        // ship's collider, which is drawn twice as large.
+        let offset = (ship_width() + ENEMY_SIZE) / 2.0 + NEAR_MISS_MARGIN - 1.0;
+        assert_eq!(pass_by(offset), (true, false));
+    }
+
+    #[test]
+    fn an_enemy_that_hits_the_ship_is_not_a_near_miss() {
+        assert_eq!(pass_by(0.0), (false, true));
+    }
+
+    #[test]
+    fn one_contact_costs_one_shared_life() {
+        let params = SimParams {
+            spawn_rate_scale: 0.0,
+            coop: Some(Coop {
+                shared_lives: true,
+                friendly_fire: false,
+            }),
+            ..SimParams::default()
+        };
+        let mut world = World::new(
+            vec2(800.0, 600.0),
+            Preset::Normal.difficulty(),
+            PoolCaps::default(),
+            1,
+            params,
+        );
+        let lives = params.player_hp * 2;
+        let ship = world.ships[0];
+        let pos = world.ship_pos(ship);
+        world.spawn_enemy(EnemyKind::Small, pos, ENEMY_SIZE, 10.0);
+        let mut hits = 0;
+        for _ in 0..30 {
+            let inputs = [Input::default(); 2];
+            for event in world.step(&inputs, DELTA).iter() {
+                if let Event::PlayerHit { player: 0, .. } = event {
+                    hits += 1;
+                }
+            }
+        }
+        assert_eq!(hits, 1);
+        assert!(!world.game_over);
+        for &ship in &world.ships {
+            assert_eq!(world.ship_health(ship).hp, lives - 1);
+        }
+        assert!(world.enemies.is_empty());
+    }
+
+    #[test]
+    fn a_lingering_enemy_costs_one_hit_point_without_invulnerability() {
+        let params = SimParams {
+            spawn_rate_scale: 0.0,
+            player_hp: 3,
+            player_invulnerability: 0.0,
+            ..SimParams::default()
+        };
+        let mut world = World::new(
+            vec2(800.0, 600.0),
+            Preset::Normal.difficulty(),
+            PoolCaps::default(),
+            1,
+            params,
+        );
+        let ship = world.ships[0];
+        let pos = world.ship_pos(ship);
+        world.spawn_enemy(EnemyKind::Big, pos, 60.0, 1.0);
+        for _ in 0..30 {
+            world.step(&[Input::default()], DELTA);
+        }
+        assert_eq!(world.ship_health(ship).hp, 2);
+    }
+
+    #[test]
+    fn an_enemy_far_off_is_not_a_near_miss() {
+        let offset = (ship_width() + ENEMY_SIZE) / 2.0 + NEAR_MISS_MARGIN + 10.0;
+        assert_eq!(pass_by(offset), (false, false));
+    }
+}
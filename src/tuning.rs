@@ -0,0 +1,421 @@
+//! Gameplay tuning loaded from `assets/tuning.toml`.
+//!
+//! Every section and field is optional; anything left out keeps the built-in
+//! default, so the file only needs what a designer is changing. Values are
+//! checked after parsing and a bad one is reported by its `section.field`
+//! name. [`Tuning::apply`] makes the tuned values the base [`SimParams`] of a
+//! run, which mutators and the daily challenge then adjust. Spawn rate and
+//! enemy speed scale the difficulty presets rather than replace them.
+//!
+//! Debug builds outside the browser reload the file when it changes, through
+//! a [`TuningWatcher`]. New values take effect from the next run. Each
+//! [`Replay`](crate::replay::Replay) keeps the tuning its run was played with,
+//! so a reload never changes how a recorded run plays back.
+
+use crate::enemy::EnemyKind;
+use crate::world::{
+    SimParams, BULLET_DAMAGE, BULLET_PIERCE, BULLET_SIZE, BULLET_SPEED, FIRE_COOLDOWN, PLAYER_HP,
+    PLAYER_INVULNERABILITY, PLAYER_SPEED,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Relative to the assets folder.
+pub const TUNING_FILE: &str = "tuning.toml";
+/// The same file, relative to the working directory, for reloading.
+pub const TUNING_PATH: &str = "assets/tuning.toml";
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlayerTuning {
+    /// Pixels per second.
+    pub speed: f32,
+    pub hp: u32,
+    /// Seconds the ship ignores damage after a hit.
+    pub invulnerability: f32,
+    /// Seconds between shots.
+    pub fire_cooldown: f32,
+}
+
+impl Default for PlayerTuning {
+    fn default() -> Self {
+        PlayerTuning {
+            speed: PLAYER_SPEED,
+            hp: PLAYER_HP,
+            invulnerability: PLAYER_INVULNERABILITY,
+            fire_cooldown: FIRE_COOLDOWN,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct BulletTuning {
+    pub size: f32,
+    /// Pixels per second.
+    pub speed: f32,
+    pub damage: u32,
+    /// Enemies one bullet can hit.
+    pub pierce: u32,
+}
+
+impl Default for BulletTuning {
+    fn default() -> Self {
+        BulletTuning {
+            size: BULLET_SIZE,
+            speed: BULLET_SPEED,
+            damage: BULLET_DAMAGE,
+            pierce: BULLET_PIERCE,
+        }
+    }
+}
+
+/// Smallest and largest size each kind of enemy spawns at.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnemyTuning {
+    pub small_size: [f32; 2],
+    pub medium_size: [f32; 2],
+    pub big_size: [f32; 2],
+}
+
+impl EnemyTuning {
+    /// Size ranges indexed like [`EnemyKind::ALL`].
+    pub fn sizes(&self) -> [[f32; 2]; 3] {
+        [self.small_size, self.medium_size, self.big_size]
+    }
+}
+
+impl Default for EnemyTuning {
+    fn default() -> Self {
+        let [small_size, medium_size, big_size] = EnemyKind::ALL.map(|kind| {
+            let (min, max) = kind.size_range();
+            [min, max]
+        });
+        EnemyTuning {
+            small_size,
+            medium_size,
+            big_size,
+        }
+    }
+}
+
+/// Multipliers on the difficulty preset's curves.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpawnTuning {
+    pub rate_scale: f32,
+    pub speed_scale: f32,
+}
+
+impl Default for SpawnTuning {
+    fn default() -> Self {
+        SpawnTuning {
+            rate_scale: 1.0,
+            speed_scale: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct EffectTuning {
+    /// Explosion particles per pixel of the killed enemy's size.
+    pub explosion_particles: f32,
+}
+
+impl Default for EffectTuning {
+    fn default() -> Self {
+        EffectTuning {
+            explosion_particles: 3.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tuning {
+    pub player: PlayerTuning,
+    pub bullets: BulletTuning,
+    pub enemies: EnemyTuning,
+    pub spawning: SpawnTuning,
+    pub effects: EffectTuning,
+}
+
+#[derive(Debug)]
+pub enum TuningError {
+    Read(String),
+    /// Not valid TOML, or not the expected shape.
+    Parse {
+        line: Option<usize>,
+        message: String,
+    },
+    /// Parsed, but a value is out of range.
+    Invalid {
+        field: &'static str,
+        message: String,
+    },
+}
+
+impl fmt::Display for TuningError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TuningError::Read(error) => write!(f, "can't read {}: {}", TUNING_FILE, error),
+            TuningError::Parse {
+                line: Some(line),
+                message,
+            } => write!(f, "{} line {}: {}", TUNING_FILE, line, message),
+            TuningError::Parse {
+                line: None,
+                message,
+            } => write!(f, "{}: {}", TUNING_FILE, message),
+            TuningError::Invalid { field, message } => {
+                write!(f, "{}: {} {}", TUNING_FILE, field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TuningError {}
+
+fn positive(field: &'static str, value: f32) -> Result<(), TuningError> {
+    if value > 0.0 && value.is_finite() {
+        Ok(())
+    } else {
+        Err(TuningError::Invalid {
+            field,
+            message: format!("must be more than 0, got {}", value),
+        })
+    }
+}
+
+fn not_negative(field: &'static str, value: f32) -> Result<(), TuningError> {
+    if value >= 0.0 && value.is_finite() {
+        Ok(())
+    } else {
+        Err(TuningError::Invalid {
+            field,
+            message: format!("must be 0 or more, got {}", value),
+        })
+    }
+}
+
+fn at_least_one(field: &'static str, value: u32) -> Result<(), TuningError> {
+    if value >= 1 {
+        Ok(())
+    } else {
+        Err(TuningError::Invalid {
+            field,
+            message: "must be at least 1".to_string(),
+        })
+    }
+}
+
+fn size_range(field: &'static str, [min, max]: [f32; 2]) -> Result<(), TuningError> {
+    positive(field, min)?;
+    positive(field, max)?;
+    if min <= max {
+        Ok(())
+    } else {
+        Err(TuningError::Invalid {
+            field,
+            message: format!("must be [smallest, largest], got [{}, {}]", min, max),
+        })
+    }
+}
+
+impl Tuning {
+    /// Loads the tuning file from the assets folder. A missing file is not an
+    /// error; the defaults are the game's own tuning.
+    pub async fn load() -> Result<Tuning, TuningError> {
+        match macroquad::file::load_string(TUNING_FILE).await {
+            Ok(text) => Tuning::parse(&text),
+            Err(_) => Ok(Tuning::default()),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Tuning, TuningError> {
+        let tuning: Tuning = toml::from_str(text).map_err(|error| TuningError::Parse {
+            line: error
+                .span()
+                .map(|span| text[..span.start].matches('\n').count() + 1),
+            message: error.message().to_string(),
+        })?;
+        tuning.validate()?;
+        Ok(tuning)
+    }
+
+    pub fn validate(&self) -> Result<(), TuningError> {
+        let player = &self.player;
+        positive("player.speed", player.speed)?;
+        at_least_one("player.hp", player.hp)?;
+        not_negative("player.invulnerability", player.invulnerability)?;
+        not_negative("player.fire_cooldown", player.fire_cooldown)?;
+        let bullets = &self.bullets;
+        positive("bullets.size", bullets.size)?;
+        positive("bullets.speed", bullets.speed)?;
+        at_least_one("bullets.damage", bullets.damage)?;
+        at_least_one("bullets.pierce", bullets.pierce)?;
+        size_range("enemies.small_size", self.enemies.small_size)?;
+        size_range("enemies.medium_size", self.enemies.medium_size)?;
+        size_range("enemies.big_size", self.enemies.big_size)?;
+        not_negative("spawning.rate_scale", self.spawning.rate_scale)?;
+        positive("spawning.speed_scale", self.spawning.speed_scale)?;
+        not_negative(
+            "effects.explosion_particles",
+            self.effects.explosion_particles,
+        )?;
+        Ok(())
+    }
+
+    /// Sets the base parameters a run is simulated with.
+    pub fn apply(&self, params: &mut SimParams) {
+        params.player_speed = self.player.speed;
+        params.player_hp = self.player.hp;
+        params.player_invulnerability = self.player.invulnerability;
+        params.fire_cooldown = self.player.fire_cooldown;
+        params.bullet_size = self.bullets.size;
+        params.bullet_speed = self.bullets.speed;
+        params.bullet_damage = self.bullets.damage;
+        params.bullet_pierce = self.bullets.pierce;
+        params.enemy_sizes = self.enemies.sizes();
+        params.spawn_rate_scale = self.spawning.rate_scale;
+        params.enemy_speed_scale = self.spawning.speed_scale;
+    }
+}
+
+/// Polls the tuning file's modification time and reloads it when it changes.
+#[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+pub struct TuningWatcher {
+    modified: Option<std::time::SystemTime>,
+    timer: f32,
+}
+
+#[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+impl TuningWatcher {
+    /// Seconds between checks.
+    const INTERVAL: f32 = 0.5;
+
+    pub fn new() -> Self {
+        TuningWatcher {
+            modified: TuningWatcher::modified(),
+            timer: 0.0,
+        }
+    }
+
+    fn modified() -> Option<std::time::SystemTime> {
+        std::fs::metadata(TUNING_PATH)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    /// The reloaded tuning, or why it couldn't be, if the file changed since
+    /// the last check.
+    pub fn poll(&mut self, delta: f32) -> Option<Result<Tuning, TuningError>> {
+        self.timer += delta;
+        if self.timer < TuningWatcher::INTERVAL {
+            return None;
+        }
+        self.timer = 0.0;
+        let modified = TuningWatcher::modified();
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(
+            std::fs::read_to_string(TUNING_PATH)
+                .map_err(|error| TuningError::Read(error.to_string()))
+                .and_then(|text| Tuning::parse(&text)),
+        )
+    }
+}
+
+#[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
+impl Default for TuningWatcher {
+    fn default() -> Self {
+        TuningWatcher::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid_field(text: &str) -> &'static str {
+        match Tuning::parse(text) {
+            Err(TuningError::Invalid { field, .. }) => field,
+            other => panic!("{:?} for {}", other, text),
+        }
+    }
+
+    #[test]
+    fn the_shipped_file_is_the_defaults() {
+        let tuning = Tuning::parse(include_str!("../assets/tuning.toml")).unwrap();
+        assert_eq!(tuning, Tuning::default());
+    }
+
+    #[test]
+    fn an_empty_file_is_the_defaults() {
+        assert_eq!(Tuning::parse("").unwrap(), Tuning::default());
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let mut tuning = Tuning::default();
+        tuning.player.hp = 3;
+        tuning.player.invulnerability = 0.5;
+        tuning.bullets.pierce = 2;
+        tuning.enemies.big_size = [50.0, 80.0];
+        tuning.spawning.rate_scale = 0.0;
+        let text = toml::to_string(&tuning).unwrap();
+        assert_eq!(Tuning::parse(&text).unwrap(), tuning);
+    }
+
+    #[test]
+    fn accepts_values_at_the_edge_of_their_range() {
+        let text = "
+            [player]
+            hp = 5
+            invulnerability = 0.0
+            fire_cooldown = 0.0
+            [enemies]
+            small_size = [20.0, 20.0]
+            [spawning]
+            rate_scale = 0.0
+            [effects]
+            explosion_particles = 0.0
+        ";
+        let tuning = Tuning::parse(text).unwrap();
+        assert_eq!(tuning.player.hp, 5);
+        assert_eq!(tuning.enemies.small_size, [20.0, 20.0]);
+    }
+
+    #[test]
+    fn rejects_values_out_of_range_by_field() {
+        assert_eq!(invalid_field("player.speed = 0.0"), "player.speed");
+        assert_eq!(invalid_field("[player]\nspeed = 0.0"), "player.speed");
+        assert_eq!(invalid_field("[player]\nhp = 0"), "player.hp");
+        assert_eq!(
+            invalid_field("[player]\ninvulnerability = -1.0"),
+            "player.invulnerability"
+        );
+        assert_eq!(invalid_field("[bullets]\nspeed = inf"), "bullets.speed");
+        assert_eq!(invalid_field("[bullets]\npierce = 0"), "bullets.pierce");
+        assert_eq!(
+            invalid_field("[enemies]\nbig_size = [64.0, 48.0]"),
+            "enemies.big_size"
+        );
+        assert_eq!(
+            invalid_field("[spawning]\nspeed_scale = 0.0"),
+            "spawning.speed_scale"
+        );
+    }
+
+    #[test]
+    fn reports_the_line_of_a_parse_error() {
+        match Tuning::parse("[player]\nhp = 1\nlives = 3\n") {
+            Err(TuningError::Parse { line, .. }) => assert_eq!(line, Some(3)),
+            other => panic!("{:?}", other),
+        }
+    }
+}
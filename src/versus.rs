@@ -0,0 +1,245 @@
+//! Versus, a score battle between two local players.
+//!
+//! Both ships share the arena for a timed round. Kills score for the ship that
+//! fired. Ships never go down; every hit taken costs [`HIT_PENALTY`] points
+//! instead and, with garbage on,
+//! every [`GARBAGE_CHAIN`] kills in a row without being hit drop
+//! [`GARBAGE_ENEMIES`] extra enemies on the opponent. The higher score takes
+//! the round, and rounds are played as a best-of-N [`Match`].
+
+use crate::enemy::EnemyKind;
+use crate::world::{World, MAX_PLAYERS};
+use macroquad::prelude::*;
+
+/// Length of a round in seconds.
+pub const ROUND_SECONDS: f32 = 90.0;
+/// Points a ship loses for every hit it takes.
+pub const HIT_PENALTY: u32 = 100;
+/// Kills in a row that send garbage to the opponent.
+pub const GARBAGE_CHAIN: u32 = 5;
+pub const GARBAGE_ENEMIES: usize = 3;
+/// How far to either side of the opponent garbage drops.
+const GARBAGE_SPREAD: f32 = 80.0;
+
+/// Match lengths the menu offers.
+pub const BEST_OF: [u32; 3] = [1, 3, 5];
+
+/// Rules for a versus round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Versus {
+    /// Whether kill chains send enemies to the opponent.
+    pub garbage: bool,
+}
+
+impl Default for Versus {
+    fn default() -> Self {
+        Versus { garbage: true }
+    }
+}
+
+/// Drops garbage enemies just above the opponent of `from`'s player, wherever
+/// it is flying.
+pub(crate) fn send_garbage(world: &mut World, from: usize) {
+    let Some(&target) = world.ships.get(1 - from) else {
+        return;
+    };
+    if !world.ship_alive(target) {
+        return;
+    }
+    let x = world.ship_pos(target).x;
+    let kind = EnemyKind::Small;
+    let (size, _) = world.params.enemy_size(kind);
+    let progress = world
+        .difficulty
+        .progress(world.stats.time_survived, world.score);
+    let (_, speed) = world.difficulty.speed_range(progress);
+    for _ in 0..GARBAGE_ENEMIES {
+        let offset = world.rng.gen_range(-GARBAGE_SPREAD, GARBAGE_SPREAD);
+        let x = (x + offset).clamp(size / 2.0, (world.arena.x - size / 2.0).max(size / 2.0));
+        let speed = speed * world.speed_scale * world.params.enemy_speed_scale;
+        world.spawn_enemy(kind, vec2(x, -size), size, speed);
+    }
+}
+
+/// The round's winner by score, or `None` for a draw.
+pub fn round_winner(scores: &[u32]) -> Option<usize> {
+    let best = *scores.iter().max()?;
+    let mut leaders = scores
+        .iter()
+        .enumerate()
+        .filter(|&(_, &score)| score == best);
+    let (winner, _) = leaders.next()?;
+    match leaders.next() {
+        Some(_) => None,
+        None => Some(winner),
+    }
+}
+
+/// Rounds won so far in a best-of-N match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Match {
+    pub best_of: u32,
+    pub wins: [u32; MAX_PLAYERS],
+    /// Rounds played, draws included.
+    pub rounds: u32,
+}
+
+impl Match {
+    pub fn new(best_of: u32) -> Self {
+        Match {
+            best_of,
+            wins: [0; MAX_PLAYERS],
+            rounds: 0,
+        }
+    }
+
+    pub fn wins_needed(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+
+    /// Counts a finished round and returns its winner, or `None` for a draw.
+    pub fn record(&mut self, scores: &[u32]) -> Option<usize> {
+        self.rounds += 1;
+        let winner = round_winner(scores)?;
+        self.wins[winner] += 1;
+        Some(winner)
+    }
+
+    /// The player who has won the match, if anyone has yet.
+    pub fn winner(&self) -> Option<usize> {
+        self.wins
+            .iter()
+            .position(|&wins| wins >= self.wins_needed())
+    }
+
+    /// The same length of match, from the start.
+    pub fn restarted(&self) -> Self {
+        Match::new(self.best_of)
+    }
+
+    /// The next length the menu offers.
+    pub fn next_best_of(&self) -> u32 {
+        let index = BEST_OF
+            .iter()
+            .position(|&best_of| best_of == self.best_of)
+            .map_or(0, |index| (index + 1) % BEST_OF.len());
+        BEST_OF[index]
+    }
+}
+
+impl Default for Match {
+    fn default() -> Self {
+        Match::new(BEST_OF[1])
+    }
+}
+
+/// How a versus round ended, for the results screen.
+#[derive(Clone, Copy, Debug)]
+pub struct RoundResult {
+    /// `None` for a draw.
+    pub winner: Option<usize>,
+    /// The match after this round.
+    pub standings: Match,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::difficulty::Preset;
+    use crate::events::Event;
+    use crate::pool::PoolCaps;
+    use crate::world::{Input, SimParams};
+
+    const DELTA: f32 = 1.0 / 60.0;
+
+    fn versus_world() -> World {
+        let params = SimParams {
+            spawn_rate_scale: 0.0,
+            versus: Some(Versus::default()),
+            ..SimParams::default()
+        };
+        World::new(
+            vec2(800.0, 600.0),
+            Preset::Normal.difficulty(),
+            PoolCaps::default(),
+            1,
+            params,
+        )
+    }
+
+    #[test]
+    fn the_higher_score_wins_the_round() {
+        assert_eq!(round_winner(&[300, 200]), Some(0));
+        assert_eq!(round_winner(&[0, 1]), Some(1));
+    }
+
+    #[test]
+    fn equal_scores_are_a_draw() {
+        assert_eq!(round_winner(&[200, 200]), None);
+        assert_eq!(round_winner(&[0, 0]), None);
+        assert_eq!(round_winner(&[]), None);
+    }
+
+    #[test]
+    fn a_best_of_three_goes_to_the_first_to_two_wins() {
+        let mut standings = Match::new(3);
+        assert_eq!(standings.wins_needed(), 2);
+        assert_eq!(standings.record(&[100, 50]), Some(0));
+        assert_eq!(standings.winner(), None);
+        assert_eq!(standings.record(&[100, 100]), None);
+        assert_eq!(standings.record(&[0, 50]), Some(1));
+        assert_eq!(standings.winner(), None);
+        assert_eq!(standings.record(&[10, 5]), Some(0));
+        assert_eq!(standings.winner(), Some(0));
+        assert_eq!(standings.rounds, 4);
+        assert_eq!(standings.wins, [2, 1]);
+    }
+
+    #[test]
+    fn restarting_keeps_the_length_only() {
+        let mut standings = Match::new(5);
+        standings.record(&[1, 0]);
+        assert_eq!(standings.restarted(), Match::new(5));
+    }
+
+    #[test]
+    fn the_menu_cycles_through_the_lengths() {
+        assert_eq!(Match::new(1).next_best_of(), 3);
+        assert_eq!(Match::new(3).next_best_of(), 5);
+        assert_eq!(Match::new(5).next_best_of(), 1);
+        assert_eq!(Match::new(7).next_best_of(), BEST_OF[0]);
+    }
+
+    #[test]
+    fn a_round_ends_when_the_time_is_up() {
+        let mut world = versus_world();
+        let inputs = [Input::default(); MAX_PLAYERS];
+        let steps = (ROUND_SECONDS / DELTA).ceil() as usize;
+        let mut finished = 0;
+        for step in 0..steps + 10 {
+            let events = world.step(&inputs, DELTA);
+            if events.iter().any(|event| *event == Event::RunFinished) {
+                finished += 1;
+                assert!(step + 2 >= steps, "ended after {} steps", step);
+            }
+        }
+        assert!(world.game_over);
+        assert_eq!(finished, 1);
+    }
+
+    #[test]
+    fn a_hit_costs_points_but_not_the_ship() {
+        let mut world = versus_world();
+        let ship = world.ships[0];
+        world.players.get_mut(ship).unwrap().score = 250;
+        let pos = world.ship_pos(ship);
+        world.spawn_enemy(EnemyKind::Small, pos, 20.0, 10.0);
+        let inputs = [Input::default(); MAX_PLAYERS];
+        for _ in 0..30 {
+            world.step(&inputs, DELTA);
+        }
+        assert!(world.ship_alive(ship));
+        assert!(!world.game_over);
+        assert_eq!(world.players.get(ship).unwrap().score, 250 - HIT_PENALTY);
+    }
+}
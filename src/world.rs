@@ -0,0 +1,410 @@
+//! The gameplay simulation.
+//!
+//! [`World`] owns every entity and component of a run plus the run-wide state
+//! (score, combos, statistics, difficulty, game mode and its own RNG).
+//! [`World::step`] runs the systems in a fixed order: input, AI, movement,
+//! collision, damage, cleanup, then the game mode's rules. Nothing in here
+//! touches the window, audio or the global RNG, so a world can be stepped
+//! headless; rendering reads the component stores and everything else reacts
+//! to the [`Event`]s a step publishes.
+
+use crate::broadphase::Grid;
+use crate::components::{Body, Enemy, Health, Player, Projectile, Transform, Velocity};
+use crate::difficulty::Difficulty;
+use crate::ecs::{Components, Entities, Entity};
+use crate::enemy::EnemyKind;
+use crate::events::{Event, EventQueue};
+use crate::hits::{Hit, Overlap};
+use crate::modes::ModeKind;
+use crate::pool::PoolCaps;
+use crate::scoring::{Combo, ComboConfig};
+use crate::sprites;
+use crate::stats::RunStats;
+use crate::systems;
+use crate::versus::{Versus, ROUND_SECONDS};
+use macroquad::prelude::*;
+use macroquad::rand::RandGenerator;
+
+pub const PLAYER_SPEED: f32 = 200.0;
+/// The ship goes down on its first hit.
+pub const PLAYER_HP: u32 = 1;
+/// Seconds of invulnerability after the player is hit.
+pub const PLAYER_INVULNERABILITY: f32 = 0.0;
+pub const FIRE_COOLDOWN: f32 = 0.2;
+pub const BULLET_SIZE: f32 = 32.0;
+pub const BULLET_SPEED: f32 = 400.0;
+pub const BULLET_DAMAGE: u32 = 1;
+pub const BULLET_PIERCE: u32 = 1;
+pub const NEAR_MISS_MARGIN: f32 = 24.0;
+/// Most ships a world can have, one per local player.
+pub const MAX_PLAYERS: usize = 2;
+const GRID_CELL_SIZE: f32 = 64.0;
+/// Ships are drawn at twice the sprite's pixel size.
+const SHIP_SCALE: f32 = 2.0;
+
+/// Rules for a run with a second ship.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Coop {
+    /// Both ships draw from one pool of hit points instead of each having
+    /// their own.
+    pub shared_lives: bool,
+    /// Shots hit the other ship too.
+    pub friendly_fire: bool,
+}
+
+/// Tunables a run is simulated with, fixed when the world is built. The
+/// defaults are the constants above. The [tuning file](crate::tuning) sets
+/// them first, then [`Mutators::apply`](crate::mutators::Mutators::apply) and
+/// the daily challenge adjust them.
+#[derive(Clone, Copy, Debug)]
+pub struct SimParams {
+    pub player_speed: f32,
+    pub player_hp: u32,
+    /// Seconds a ship ignores damage after a hit.
+    pub player_invulnerability: f32,
+    /// Ship acceleration in pixels per second squared, or `None` to reach
+    /// full speed at once.
+    pub player_drift: Option<f32>,
+    /// Swaps left and right input.
+    pub mirror_controls: bool,
+    pub fire_cooldown: f32,
+    pub bullet_size: f32,
+    pub bullet_speed: f32,
+    pub bullet_damage: u32,
+    pub bullet_pierce: u32,
+    /// Smallest and largest size of each kind of enemy, indexed like
+    /// [`EnemyKind::ALL`].
+    pub enemy_sizes: [[f32; 2]; 3],
+    /// Spawns every enemy at its kind's largest size.
+    pub giant_enemies: bool,
+    /// Multipliers on the difficulty curve's spawn rate and enemy speeds,
+    /// on top of the per-frame scales.
+    pub spawn_rate_scale: f32,
+    pub enemy_speed_scale: f32,
+    /// How far around the ship is visible, or `None` for everything. Only
+    /// rendering reads it.
+    pub vision_radius: Option<f32>,
+    /// A second ship and the rules the two play by.
+    pub coop: Option<Coop>,
+    /// A second ship competing instead of cooperating.
+    pub versus: Option<Versus>,
+}
+
+impl Default for SimParams {
+    fn default() -> Self {
+        SimParams {
+            player_speed: PLAYER_SPEED,
+            player_hp: PLAYER_HP,
+            player_invulnerability: PLAYER_INVULNERABILITY,
+            player_drift: None,
+            mirror_controls: false,
+            fire_cooldown: FIRE_COOLDOWN,
+            bullet_size: BULLET_SIZE,
+            bullet_speed: BULLET_SPEED,
+            bullet_damage: BULLET_DAMAGE,
+            bullet_pierce: BULLET_PIERCE,
+            enemy_sizes: EnemyKind::ALL.map(|kind| {
+                let (min, max) = kind.size_range();
+                [min, max]
+            }),
+            giant_enemies: false,
+            spawn_rate_scale: 1.0,
+            enemy_speed_scale: 1.0,
+            vision_radius: None,
+            coop: None,
+            versus: None,
+        }
+    }
+}
+
+impl SimParams {
+    /// Smallest and largest size `kind` spawns at.
+    pub fn enemy_size(&self, kind: EnemyKind) -> (f32, f32) {
+        let index = EnemyKind::ALL
+            .iter()
+            .position(|&other| other == kind)
+            .unwrap_or(0);
+        let [min, max] = self.enemy_sizes[index];
+        (min, max)
+    }
+}
+
+/// One frame of input for one ship, from whatever is controlling it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Input {
+    /// Each axis from -1.0 to 1.0; up is negative y.
+    pub movement: Vec2,
+    pub fire: bool,
+}
+
+/// Something that flies a ship: a keyboard layout, or the
+/// [`Bot`](crate::bot::Bot).
+pub trait Controller {
+    /// Input for `ship` this step, given the world as the step starts.
+    fn input(&mut self, world: &World, ship: Entity) -> Input;
+}
+
+pub struct World {
+    /// Seed of the world's RNG; the same seed and inputs replay the same run.
+    pub seed: u64,
+    pub arena: Vec2,
+    pub caps: PoolCaps,
+    pub difficulty: Difficulty,
+    pub mode: ModeKind,
+    pub params: SimParams,
+    /// Multipliers applied on top of the difficulty curve, e.g. by adaptive
+    /// difficulty.
+    pub spawn_scale: f32,
+    pub speed_scale: f32,
+    pub rng: RandGenerator,
+    pub score: u32,
+    /// Each player's combo, by player index. Kills, misses and hits only
+    /// count towards the combo of the ship that made or took them.
+    pub combos: [Combo; MAX_PLAYERS],
+    pub stats: RunStats,
+    pub game_over: bool,
+    /// Events published by the last [`World::step`].
+    pub events: EventQueue,
+
+    pub entities: Entities,
+    pub transforms: Components<Transform>,
+    pub velocities: Components<Velocity>,
+    pub bodies: Components<Body>,
+    pub healths: Components<Health>,
+    pub players: Components<Player>,
+    pub enemies: Components<Enemy>,
+    pub projectiles: Components<Projectile>,
+    /// The ships, by player index. A destroyed ship stays in the world with
+    /// no hit points left.
+    pub ships: Vec<Entity>,
+
+    pub(crate) grid: Grid,
+    pub(crate) grid_entities: Vec<Entity>,
+    pub(crate) candidates: Vec<usize>,
+    pub(crate) overlaps: Vec<Overlap>,
+    /// Ships touching enemies, as `(ship, enemy)`.
+    pub(crate) player_contacts: Vec<(Entity, Entity)>,
+    /// Projectiles touching a ship other than their owner, as
+    /// `(projectile, ship)`.
+    pub(crate) friendly_overlaps: Vec<(Entity, Entity)>,
+    pub(crate) hits: Vec<Hit>,
+    pub(crate) doomed: Vec<Entity>,
+}
+
+impl World {
+    pub fn new(
+        arena: Vec2,
+        difficulty: Difficulty,
+        caps: PoolCaps,
+        seed: u64,
+        params: SimParams,
+    ) -> Self {
+        let rng = RandGenerator::new();
+        rng.srand(seed);
+        let mut world = World {
+            seed,
+            arena,
+            caps,
+            difficulty,
+            mode: ModeKind::Classic,
+            params,
+            spawn_scale: 1.0,
+            speed_scale: 1.0,
+            rng,
+            score: 0,
+            combos: std::array::from_fn(|_| Combo::new(ComboConfig::default())),
+            stats: RunStats::default(),
+            game_over: false,
+            events: EventQueue::new(),
+            entities: Entities::new(caps.projectiles + caps.enemies + MAX_PLAYERS),
+            transforms: Components::new(),
+            velocities: Components::new(),
+            bodies: Components::new(),
+            healths: Components::new(),
+            players: Components::new(),
+            enemies: Components::new(),
+            projectiles: Components::new(),
+            ships: vec![],
+            grid: Grid::new(GRID_CELL_SIZE),
+            grid_entities: vec![],
+            candidates: vec![],
+            overlaps: vec![],
+            player_contacts: vec![],
+            friendly_overlaps: vec![],
+            hits: vec![],
+            doomed: vec![],
+        };
+        if params.coop.is_some() || params.versus.is_some() {
+            let offset = vec2(arena.x / 6.0, 0.0);
+            let hp = if params.coop.is_some_and(|coop| coop.shared_lives) {
+                params.player_hp * 2
+            } else {
+                params.player_hp
+            };
+            world.spawn_player(arena / 2.0 - offset, hp);
+            world.spawn_player(arena / 2.0 + offset, hp);
+        } else {
+            world.spawn_player(arena / 2.0, params.player_hp);
+        }
+        world
+    }
+
+    fn spawn_player(&mut self, pos: Vec2, hp: u32) -> Entity {
+        let entity = self
+            .entities
+            .insert(())
+            .expect("the entity pool has room for the player");
+        self.transforms.insert(entity, Transform { pos });
+        self.velocities.insert(entity, Velocity { vel: Vec2::ZERO });
+        self.bodies.insert(
+            entity,
+            Body {
+                size: sprites::SHIP.frame_size().x,
+                collider: sprites::SHIP.collider(sprites::SHIP.frame_size() * SHIP_SCALE),
+            },
+        );
+        self.healths.insert(entity, Health::new(hp));
+        self.players.insert(
+            entity,
+            Player {
+                index: self.ships.len(),
+                score: 0,
+                chain: 0,
+                speed: self.params.player_speed,
+                fire_cooldown: 0.0,
+                movement: Vec2::ZERO,
+            },
+        );
+        self.ships.push(entity);
+        entity
+    }
+
+    pub(crate) fn spawn_projectile(&mut self, owner: Entity, pos: Vec2) -> Option<Entity> {
+        if self.projectiles.len() >= self.caps.projectiles {
+            return None;
+        }
+        let entity = self.entities.insert(())?;
+        self.transforms.insert(entity, Transform { pos });
+        self.velocities.insert(
+            entity,
+            Velocity {
+                vel: vec2(0.0, -self.params.bullet_speed),
+            },
+        );
+        self.bodies.insert(
+            entity,
+            Body {
+                size: self.params.bullet_size,
+                collider: sprites::BULLET.collider(Vec2::splat(self.params.bullet_size)),
+            },
+        );
+        self.projectiles.insert(
+            entity,
+            Projectile::new(owner, self.params.bullet_damage, self.params.bullet_pierce),
+        );
+        Some(entity)
+    }
+
+    pub(crate) fn spawn_enemy(
+        &mut self,
+        kind: EnemyKind,
+        pos: Vec2,
+        size: f32,
+        speed: f32,
+    ) -> Option<Entity> {
+        if self.enemies.len() >= self.caps.enemies {
+            return None;
+        }
+        let entity = self.entities.insert(())?;
+        self.transforms.insert(entity, Transform { pos });
+        self.velocities.insert(
+            entity,
+            Velocity {
+                vel: vec2(0.0, speed),
+            },
+        );
+        self.bodies.insert(
+            entity,
+            Body {
+                size,
+                collider: sprites::enemy(kind).collider(vec2(size, size)),
+            },
+        );
+        self.healths.insert(entity, Health::new(kind.max_hp()));
+        self.enemies.insert(entity, Enemy { kind });
+        Some(entity)
+    }
+
+    pub fn despawn(&mut self, entity: Entity) {
+        if !self.entities.remove(entity) {
+            return;
+        }
+        self.transforms.remove(entity);
+        self.velocities.remove(entity);
+        self.bodies.remove(entity);
+        self.healths.remove(entity);
+        self.players.remove(entity);
+        self.enemies.remove(entity);
+        self.projectiles.remove(entity);
+    }
+
+    pub fn ship_pos(&self, ship: Entity) -> Vec2 {
+        self.transforms
+            .get(ship)
+            .map_or(self.arena / 2.0, |transform| transform.pos)
+    }
+
+    pub fn ship_health(&self, ship: Entity) -> Health {
+        self.healths.get(ship).copied().unwrap_or(Health::new(0))
+    }
+
+    pub fn ship_alive(&self, ship: Entity) -> bool {
+        !self.ship_health(ship).dead()
+    }
+
+    /// The longest chain any player reached this run.
+    pub fn best_chain(&self) -> u32 {
+        self.combos.iter().map(Combo::best_chain).max().unwrap_or(0)
+    }
+
+    /// Advances the simulation by `delta` seconds and returns the events
+    /// it produced. The run statistics have already seen them. `inputs` go to
+    /// the ships by player index; a ship without one stands still.
+    pub fn step(&mut self, inputs: &[Input], delta: f32) -> &EventQueue {
+        self.events.clear();
+        if self.game_over {
+            return &self.events;
+        }
+        self.stats.time_survived += delta;
+        for combo in &mut self.combos {
+            combo.update(delta);
+        }
+
+        let rules = self.mode.rules();
+        let mut allowed = [Input::default(); MAX_PLAYERS];
+        for (allowed, input) in allowed.iter_mut().zip(inputs) {
+            *allowed = Input {
+                fire: input.fire && rules.allows_shooting(),
+                ..*input
+            };
+        }
+        systems::input(self, &allowed, delta);
+        systems::ai(self, delta);
+        systems::movement(self, delta);
+        systems::collision(self);
+        systems::damage(self, delta);
+        systems::cleanup(self);
+        rules.update(self);
+        let round_over = self.params.versus.is_some() && self.stats.time_survived >= ROUND_SECONDS;
+        if !self.game_over && (rules.finished(self) || round_over) {
+            self.game_over = true;
+            self.events.publish(Event::RunFinished);
+        }
+        if self.game_over {
+            self.events.publish(Event::RunEnded { score: self.score });
+        }
+
+        self.events.dispatch(&mut self.stats);
+        &self.events
+    }
+}